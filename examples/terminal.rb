@@ -20,23 +20,19 @@ def get_cpu_move(board, best)
 
     print "\nCPU evaluated #{count} moves before choosing to "
 
-    if move.piece?
-        (from, to) = move.piece_positions
-        
-        piece, takes = board.piece(from), board.piece(to)
-        if piece && takes
-            puts "take #{takes.name}(#{to}) with #{piece.name}(#{from})"
-        elsif piece && !takes
-            puts "move #{piece.name}(#{from}) to #{to}"
-        else
-            puts "move #{from} to #{to}"
-        end
-    elsif move.kingsidecastle?
-       puts "castle kingside"
-    elsif move.queensidecastle?
-        puts "castle queenside"
-    elsif move.resign?
-        puts "resign" 
+    if move.resign?
+        puts "resign"
+        return move
+    end
+
+    info = board.move_info(move)
+    if info.castle?
+        puts "castle #{move.kingside_castle? ? "kingside" : "queenside"}"
+    elsif info.capture?
+        print "take #{info.captured.name}(#{info.capture_square}) with #{info.piece.name}(#{info.from})"
+        puts info.en_passant? ? " en passant" : ""
+    else
+        puts "move #{info.piece.name}(#{info.from}) to #{info.to}"
     end
 
     move
@@ -46,7 +42,7 @@ end
 
 
 board = Silverpoint::Board::new
-puts board
+puts board.render
 history = []
 
 best_queue = Thread::Queue.new
@@ -98,9 +94,9 @@ loop do
         next
     else
         begin
-            Silverpoint::Move::parse(s)
-        rescue Exception => e
-            puts e
+            board.parse_move(s)
+        rescue Silverpoint::Error => e
+            puts e.message
             next
         end
     end
@@ -108,15 +104,18 @@ loop do
     result = board.play_move(m)
     if result.continuing?
         board = result.next_board
-        puts board
+        puts board.render(last_move: m)
         history << m
     elsif result.victory?
-        puts board
+        puts result.final_board.render(last_move: result.last_move)
+        history << m
         puts "#{result.winning_color} wins."
         break
     elsif result.illegal_move?
         puts "#{result.illegal_move} is an illegal move"
-    elsif result.stalemate?
+    elsif result.draw?
+        puts result.final_board.render(last_move: result.last_move)
+        history << m
         puts "Drawn game."
         break
     end
@@ -0,0 +1,336 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::{Color, Move, Piece};
+use magnus::method;
+use magnus::Module;
+
+use crate::enums;
+use crate::frozen::frozen_wrap;
+use crate::geometry::{self, Direction};
+use crate::position::{from_square_index, square_index, Position};
+
+pub(crate) fn pieces(board: &chess_engine::Board) -> impl Iterator<Item = (usize, Piece)> + '_ {
+    (0..64).filter_map(|square| Some((square, board.get_piece(from_square_index(square))?)))
+}
+
+pub(crate) fn occupancy(board: &chess_engine::Board) -> u64 {
+    pieces(board).fold(0, |bits, (square, _)| bits | 1 << square)
+}
+
+pub(crate) fn slides_along(piece: Piece, direction: Direction) -> bool {
+    match piece {
+        Piece::Bishop(..) => direction.is_diagonal(),
+        Piece::Rook(..) => !direction.is_diagonal(),
+        Piece::Queen(..) => true,
+        _ => false,
+    }
+}
+
+pub(crate) fn attacks(piece: Piece, square: usize, occupied: u64) -> u64 {
+    match piece {
+        Piece::Pawn(color, _) => geometry::pawn_attacks(square, color == Color::White),
+        Piece::Knight(..) => geometry::knight_attacks(square),
+        Piece::King(..) => geometry::king_attacks(square),
+        _ => Direction::ALL
+            .into_iter()
+            .filter(|&direction| slides_along(piece, direction))
+            .fold(0, |bits, direction| {
+                bits | geometry::slide(square, direction, occupied)
+            }),
+    }
+}
+
+/// The pieces of `color` attacking `square`, counting only pieces on the `occupied` squares,
+/// so pieces can be lifted off the board as an exchange plays out.
+pub(crate) fn attackers_with(
+    board: &chess_engine::Board,
+    square: usize,
+    color: Color,
+    occupied: u64,
+) -> impl Iterator<Item = (usize, Piece)> + '_ {
+    pieces(board).filter(move |&(from, piece)| {
+        occupied & 1 << from != 0
+            && piece.get_color() == color
+            && attacks(piece, from, occupied) & 1 << square != 0
+    })
+}
+
+pub(crate) fn attackers(board: &chess_engine::Board, square: usize, color: Color) -> Vec<Piece> {
+    attackers_with(board, square, color, occupancy(board))
+        .map(|(_, piece)| piece)
+        .collect()
+}
+
+pub(crate) fn checkers(board: &chess_engine::Board, color: Color) -> Vec<Piece> {
+    board.get_king_pos(color).map_or_else(Vec::new, |king| {
+        attackers(board, square_index(king), !color)
+    })
+}
+
+/// The material won by `m` once every capture on its square is played out, in pawns.
+///
+/// Each side recaptures with its least valuable piece, sliders lined up behind each other
+/// join in as the pieces in front are traded off, and either side stops when taking
+/// would lose material. Pins are ignored. Moves that aren't captures score what the other
+/// side can win by taking the piece that moved.
+pub(crate) fn see(board: &chess_engine::Board, m: Move) -> i32 {
+    let Move::Piece(from, to) = m else {
+        return 0;
+    };
+    let Some(piece) = board.get_piece(from).filter(|_| to.is_on_board()) else {
+        return 0;
+    };
+
+    let color = piece.get_color();
+    let target = square_index(to);
+    let mut occupied = occupancy(board) & !(1 << square_index(from));
+
+    let taken = match board.get_piece(to) {
+        Some(taken) => taken.get_material_value(),
+        None if piece.is_pawn() && board.get_en_passant() == Some(to) => {
+            occupied &= !(1 << square_index(to.pawn_back(color)));
+            1
+        }
+        None => 0,
+    };
+    let promotes = piece.is_pawn() && (to.get_row() == 0 || to.get_row() == 7);
+    let queen = Piece::Queen(color, to).get_material_value();
+
+    // What each capture wins, if it's the last one.
+    let mut gains = vec![if promotes {
+        taken + queen - piece.get_material_value()
+    } else {
+        taken
+    }];
+    let mut standing = if promotes {
+        queen
+    } else {
+        piece.get_material_value()
+    };
+
+    let mut side = !color;
+    while let Some((square, attacker)) = attackers_with(board, target, side, occupied)
+        .min_by_key(|(_, attacker)| attacker.get_material_value())
+    {
+        occupied &= !(1 << square);
+        // A king can't take a defended piece.
+        if attacker.is_king()
+            && attackers_with(board, target, !side, occupied)
+                .next()
+                .is_some()
+        {
+            break;
+        }
+
+        gains.push(standing - gains.last().copied().unwrap_or_default());
+        standing = attacker.get_material_value();
+        side = !side;
+    }
+
+    // Work back from the last capture, letting each side stop rather than lose material.
+    let mut gain = gains.pop().unwrap_or_default();
+    while let Some(before) = gains.pop() {
+        gain = -(-before).max(gain);
+    }
+    gain
+}
+
+/// A piece standing alone on the line between a slider and the square it's aimed at.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct XRay {
+    pub piece: Piece,
+    pub slider: Piece,
+    /// Where the slider is aimed: the king's square for a pin, any piece's for a discovered
+    /// attack.
+    pub target: chess_engine::Position,
+}
+
+impl XRay {
+    /// The squares from the target to the slider, not counting the target.
+    pub fn ray(&self) -> impl Iterator<Item = chess_engine::Position> {
+        let slider = square_index(self.slider.get_pos());
+
+        geometry::between(square_index(self.target), slider)
+            .chain([slider])
+            .map(from_square_index)
+    }
+}
+
+fn x_rays(
+    board: &chess_engine::Board,
+    target: chess_engine::Position,
+    slider_color: Color,
+    color: Color,
+) -> Vec<XRay> {
+    let occupied = occupancy(board);
+    let target_square = square_index(target);
+
+    pieces(board)
+        .filter(|&(_, slider)| slider.get_color() == slider_color)
+        .filter_map(|(square, slider)| {
+            let direction = geometry::direction(target_square, square)?;
+            if !slides_along(slider, direction) {
+                return None;
+            }
+
+            let mut blockers = geometry::between(target_square, square)
+                .filter(|&between| occupied & 1 << between != 0);
+            let (Some(blocker), None) = (blockers.next(), blockers.next()) else {
+                return None;
+            };
+
+            let piece = board.get_piece(from_square_index(blocker))?;
+            (piece.get_color() == color).then_some(XRay {
+                piece,
+                slider,
+                target,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn pins(board: &chess_engine::Board, color: Color) -> Vec<XRay> {
+    board
+        .get_king_pos(color)
+        .map_or_else(Vec::new, |king| x_rays(board, king, !color, color))
+}
+
+/// The pieces of `color` that would uncover an attack by moving: on the other king, or on
+/// a piece worth more than the slider behind them or left undefended.
+pub(crate) fn discovered_attacks(board: &chess_engine::Board, color: Color) -> Vec<XRay> {
+    let occupied = occupancy(board);
+
+    pieces(board)
+        .filter(|&(_, target)| target.get_color() != color)
+        .flat_map(|(square, target)| {
+            x_rays(board, target.get_pos(), color, color)
+                .into_iter()
+                .filter(move |x_ray| {
+                    target.is_king()
+                        || target.get_material_value() > x_ray.slider.get_material_value()
+                        || attackers_with(board, square, !color, occupied)
+                            .next()
+                            .is_none()
+                })
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Pin(XRay);
+
+frozen_wrap!(Pin, "Silverpoint::Pin");
+
+impl From<XRay> for Pin {
+    fn from(value: XRay) -> Self {
+        Self(value)
+    }
+}
+
+impl Pin {
+    fn piece(&self) -> enums::Piece {
+        self.0.piece.into()
+    }
+
+    fn pinner(&self) -> enums::Piece {
+        self.0.slider.into()
+    }
+
+    fn king(&self) -> Position {
+        self.0.target.into()
+    }
+
+    fn ray(&self) -> Vec<Position> {
+        self.0.ray().map(Into::into).collect()
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::Pin {} {} pinned by {} {}>",
+            self.0.piece.get_name(),
+            self.0.piece.get_pos(),
+            self.0.slider.get_name(),
+            self.0.slider.get_pos(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DiscoveredAttack(XRay);
+
+frozen_wrap!(DiscoveredAttack, "Silverpoint::DiscoveredAttack");
+
+impl From<XRay> for DiscoveredAttack {
+    fn from(value: XRay) -> Self {
+        Self(value)
+    }
+}
+
+impl DiscoveredAttack {
+    fn piece(&self) -> enums::Piece {
+        self.0.piece.into()
+    }
+
+    fn attacker(&self) -> enums::Piece {
+        self.0.slider.into()
+    }
+
+    fn target(&self) -> Position {
+        self.0.target.into()
+    }
+
+    fn ray(&self) -> Vec<Position> {
+        self.0.ray().map(Into::into).collect()
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::DiscoveredAttack {} {} uncovering {} {}>",
+            self.0.piece.get_name(),
+            self.0.piece.get_pos(),
+            self.0.slider.get_name(),
+            self.0.slider.get_pos(),
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("Pin", ruby.class_object())?;
+    class.define_method("piece", method!(Pin::piece, 0))?;
+    class.define_method("pinner", method!(Pin::pinner, 0))?;
+    class.define_method("king", method!(Pin::king, 0))?;
+    class.define_method("ray", method!(Pin::ray, 0))?;
+
+    class.define_method("dup", method!(Pin::dup, 0))?;
+    class.define_method("clone", method!(Pin::dup, 0))?;
+
+    class.define_method("inspect", method!(Pin::inspect, 0))?;
+    class.define_method("==", method!(Pin::eq, 1))?;
+    class.define_method("!=", method!(Pin::ne, 1))?;
+
+    let class = module.define_class("DiscoveredAttack", ruby.class_object())?;
+    class.define_method("piece", method!(DiscoveredAttack::piece, 0))?;
+    class.define_method("attacker", method!(DiscoveredAttack::attacker, 0))?;
+    class.define_method("target", method!(DiscoveredAttack::target, 0))?;
+    class.define_method("ray", method!(DiscoveredAttack::ray, 0))?;
+
+    class.define_method("dup", method!(DiscoveredAttack::dup, 0))?;
+    class.define_method("clone", method!(DiscoveredAttack::dup, 0))?;
+
+    class.define_method("inspect", method!(DiscoveredAttack::inspect, 0))?;
+    class.define_method("==", method!(DiscoveredAttack::eq, 1))?;
+    class.define_method("!=", method!(DiscoveredAttack::ne, 1))?;
+
+    Ok(())
+}
@@ -3,52 +3,188 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+use std::time::Duration;
+
 use chess_engine::Evaluate;
-use magnus::{function, method, Module, Object};
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    typed_data::Obj,
+    Module, Object, RArray, RHash, RString, Symbol, TryConvert, Value,
+};
 
 use crate::{
-    enums::{Color, GameResult, Move, Piece},
-    position::Position,
+    attacks::{self, DiscoveredAttack, Pin},
+    book::OpeningBook,
+    codec,
+    enums::{Color, GameResult, Move, Piece, Reason},
+    errors, fen,
+    frozen::frozen_wrap,
+    gvl::{self, no_gvl},
+    illegality::{self, Illegality},
+    mate,
+    move_info::MoveInfo,
+    notation::{self, Unresolved},
+    pawns::PawnStructure,
+    polyglot,
+    position::{from_square_index, Position},
+    render, search, svg,
+    tablebase::Tablebase,
 };
 
-unsafe fn call_without_gvl<Func, FuncReturn>(func: Func) -> FuncReturn
-where
-    Func: FnMut() -> FuncReturn,
-{
-    use std::ffi::c_void;
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Board(chess_engine::Board);
 
-    unsafe extern "C" fn anon_func<Func, FuncReturn>(data: *mut c_void) -> *mut c_void
-    where
-        Func: FnMut() -> FuncReturn,
-    {
-        let mut func: Func = *Box::from_raw(data as *mut Func);
+frozen_wrap!(Board, "Silverpoint::Board");
+
+/// The castling rights of `color` as `(kingside, queenside)`.
+///
+/// `chess_engine` keeps these private, so we check whether revoking a right changes the board.
+pub(crate) fn castling_rights(
+    board: &chess_engine::Board,
+    color: chess_engine::Color,
+) -> (bool, bool) {
+    let builder = || chess_engine::BoardBuilder::from(*board);
+
+    (
+        builder().disable_kingside_castle(color).build() != *board,
+        builder().disable_queenside_castle(color).build() != *board,
+    )
+}
 
-        Box::into_raw(Box::new(func())) as *mut _
+/// Whether `color` may still castle on one side, counting only rights whose king and rook
+/// are still standing on their original squares.
+pub(crate) fn has_castling_right(
+    board: &chess_engine::Board,
+    color: chess_engine::Color,
+    kingside: bool,
+) -> bool {
+    let (kingside_right, queenside_right) = castling_rights(board, color);
+    let king_pos = chess_engine::Position::king_pos(color);
+    let rook_pos = chess_engine::Position::new(king_pos.get_row(), if kingside { 7 } else { 0 });
+
+    (if kingside {
+        kingside_right
+    } else {
+        queenside_right
+    }) && board.get_piece(king_pos) == Some(chess_engine::Piece::King(color, king_pos))
+        && board.get_piece(rook_pos) == Some(chess_engine::Piece::Rook(color, rook_pos))
+}
+
+/// Every legal move for the side to move.
+///
+/// `chess_engine` only generates one capture per pawn and one castle per king, so the moves
+/// it leaves out are added here.
+pub(crate) fn legal_moves(board: &chess_engine::Board) -> Vec<chess_engine::Move> {
+    use chess_engine::{GameResult, Move, Piece};
+
+    let mut moves = board.get_legal_moves();
+    let color = board.get_turn_color();
+    let is_legal = |m: Move| !matches!(board.play_move(m), GameResult::IllegalMove(_));
+
+    let mut extra = Vec::new();
+    for row in 0..8 {
+        for col in 0..8 {
+            let from = chess_engine::Position::new(row, col);
+            if board.get_piece(from) != Some(Piece::Pawn(color, from)) {
+                continue;
+            }
+
+            let up = from.pawn_up(color);
+            for to in [up.next_left(), up.next_right()] {
+                if to.is_on_board() && board.has_enemy_piece(to, color) {
+                    extra.push(Move::Piece(from, to));
+                }
+            }
+        }
     }
+    extra.extend([Move::KingSideCastle, Move::QueenSideCastle]);
 
-    //? SAFETY: We box the function and args to pass them over the FFI boundary.
-    let boxed_args = Box::new(func);
+    for m in extra {
+        if !moves.contains(&m) && is_legal(m) {
+            moves.push(m);
+        }
+    }
 
-    let result = rb_sys::rb_thread_call_without_gvl(
-        Some(anon_func::<Func, FuncReturn>),
-        Box::into_raw(boxed_args) as *mut _,
-        None,
-        std::ptr::null_mut(),
-    );
+    moves
+}
 
-    *Box::from_raw(result as _)
+/// Whether the side to move is checkmated, using the full set of legal moves.
+pub(crate) fn is_mate(board: &chess_engine::Board) -> bool {
+    board.is_in_check(board.get_turn_color()) && legal_moves(board).is_empty()
 }
 
-macro_rules! no_gvl {
-    ($fun:expr) => {
-        unsafe { call_without_gvl(|| $fun) }
-    };
+/// Legal moves scored for the side to move with `depth` plies of lookahead, best first.
+///
+/// Moves are scored the same way as `get_best_next_move` scores them.
+pub(crate) fn ranked_moves(
+    board: &chess_engine::Board,
+    depth: i32,
+) -> Vec<(chess_engine::Move, f64)> {
+    let color = board.get_current_player_color();
+    let mut board_count = 0;
+
+    let mut moves = legal_moves(board)
+        .into_iter()
+        .take_while(|_| !gvl::interrupted())
+        .map(|m| {
+            let value = search::Structured(board.apply_eval_move(m)).minimax(
+                depth,
+                -1_000_000.0,
+                1_000_000.0,
+                false,
+                color,
+                &mut board_count,
+            );
+            (m, value)
+        })
+        .collect::<Vec<_>>();
+    moves.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    moves
 }
 
-#[magnus::wrap(class = "Silverpoint::Board", size, free_immediately)]
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(transparent)]
-pub struct Board(chess_engine::Board);
+/// The squares a move highlights, given the board after the move was played.
+pub(crate) fn move_squares(
+    board: &chess_engine::Board,
+    m: chess_engine::Move,
+) -> Option<(chess_engine::Position, chess_engine::Position)> {
+    // The board is after the move, so the player who castled is the one not to move.
+    let king = chess_engine::Position::king_pos(!board.get_turn_color());
+
+    match m {
+        chess_engine::Move::Piece(from, to) => Some((from, to)),
+        chess_engine::Move::KingSideCastle => Some((king, king.next_right().next_right())),
+        chess_engine::Move::QueenSideCastle => Some((king, king.next_left().next_left())),
+        chess_engine::Move::Resign => None,
+    }
+}
+
+/// Searches count depth in plies after the root move, so zero is the shallowest one.
+fn check_depth(depth: i32) -> Result<i32, magnus::Error> {
+    if depth < 0 {
+        let ruby = magnus::Ruby::get().unwrap();
+        return Err(magnus::Error::new(
+            ruby.exception_arg_error(),
+            format!("depth must be at least 0, got {depth}"),
+        ));
+    }
+
+    Ok(depth)
+}
+
+fn check_mate_length(max_moves: usize) -> Result<(), magnus::Error> {
+    if max_moves == 0 {
+        let ruby = magnus::Ruby::get().unwrap();
+        return Err(magnus::Error::new(
+            ruby.exception_arg_error(),
+            "a mate takes at least 1 move",
+        ));
+    }
+
+    Ok(())
+}
 
 impl From<chess_engine::Board> for Board {
     fn from(value: chess_engine::Board) -> Self {
@@ -75,7 +211,7 @@ impl Board {
         Self(chess_engine::Board::empty())
     }
 
-    fn rating_bar(&self, len: usize) -> String {
+    fn rating_bar(&self, len: usize) -> Result<String, magnus::Error> {
         no_gvl!(self.0.rating_bar(len))
     }
 
@@ -99,7 +235,7 @@ impl Board {
         self.0.set_turn(color.into()).into()
     }
 
-    fn get_material_advantage(&self, &color: &Color) -> i32 {
+    fn get_material_advantage(&self, &color: &Color) -> Result<i32, magnus::Error> {
         no_gvl!(self.0.get_material_advantage(color.into()))
     }
 
@@ -127,11 +263,11 @@ impl Board {
         self.0.get_king_pos(color.into()).map(Into::into)
     }
 
-    fn is_threatened(&self, &pos: &Position, &ally_color: &Color) -> bool {
+    fn is_threatened(&self, &pos: &Position, &ally_color: &Color) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.is_threatened(pos.into(), ally_color.into()))
     }
 
-    fn is_in_check(&self, &color: &Color) -> bool {
+    fn is_in_check(&self, &color: &Color) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.is_in_check(color.into()))
     }
 
@@ -143,19 +279,19 @@ impl Board {
         self.0.can_queenside_castle(color.into())
     }
 
-    fn has_sufficient_material(&self, &color: &Color) -> bool {
+    fn has_sufficient_material(&self, &color: &Color) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.has_sufficient_material(color.into()))
     }
 
-    fn has_insufficient_material(&self, &color: &Color) -> bool {
+    fn has_insufficient_material(&self, &color: &Color) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.has_insufficient_material(color.into()))
     }
 
-    fn is_stalemate(&self) -> bool {
+    fn is_stalemate(&self) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.is_stalemate())
     }
 
-    fn is_checkmate(&self) -> bool {
+    fn is_checkmate(&self) -> Result<bool, magnus::Error> {
         no_gvl!(self.0.is_checkmate())
     }
 
@@ -163,8 +299,243 @@ impl Board {
         self.0.change_turn().into()
     }
 
-    fn play_move(&self, &m: &Move) -> GameResult {
-        no_gvl!(self.0.play_move(m.into()).into())
+    fn play_move(&self, &m: &Move) -> Result<GameResult, magnus::Error> {
+        let m = m.into();
+        let result = no_gvl!(self.0.play_move(m))?;
+
+        Ok(match result {
+            chess_engine::GameResult::Victory(color) if m == chess_engine::Move::Resign => {
+                GameResult::victory(color, Reason::Resignation).on(self.0, Some(m))
+            }
+            chess_engine::GameResult::IllegalMove(_) => GameResult::from(result).on(self.0, None),
+            // `chess_engine` also calls a position with no mating material stalemate.
+            chess_engine::GameResult::Stalemate => {
+                let next = self.0.apply_eval_move(m);
+                if next.has_insufficient_material(chess_engine::Color::White)
+                    && next.has_insufficient_material(chess_engine::Color::Black)
+                {
+                    GameResult::draw(Reason::InsufficientMaterial).on(next, Some(m))
+                } else {
+                    GameResult::draw(Reason::Stalemate).on(next, Some(m))
+                }
+            }
+            result => GameResult::from(result).on(self.0.apply_eval_move(m), Some(m)),
+        })
+    }
+
+    /// Like `play_move`, but raise `IllegalMoveError` rather than returning an illegal result.
+    fn play_move_checked(&self, m: &Move) -> Result<GameResult, magnus::Error> {
+        let result = self.play_move(m)?;
+
+        match result.into() {
+            chess_engine::GameResult::IllegalMove(m) => {
+                let message = match no_gvl!(illegality::explain(&self.0, m))? {
+                    Some(illegality) => format!("illegal move {m}: {}", illegality.message()),
+                    None => format!("illegal move {m}"),
+                };
+                Err(errors::illegal_move(message, m, self.0))
+            }
+            _ => Ok(result),
+        }
+    }
+
+    fn dump(&self, _level: i32) -> RString {
+        codec::binary_string(&codec::encode_board(&self.0))
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        codec::decode_board(&codec::string_bytes(dump))
+            .map(Self)
+            .ok_or_else(|| codec::invalid_dump("Board"))
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn to_h(&self) -> Result<RHash, magnus::Error> {
+        let ruby = magnus::Ruby::get().unwrap();
+
+        let pieces = ruby.ary_new();
+        for index in 0..64 {
+            if let Some(piece) = self.0.get_piece(from_square_index(index)) {
+                pieces.push(codec::piece_to_h(piece)?)?;
+            }
+        }
+
+        let mut castling = String::new();
+        for (color, sides) in [
+            (chess_engine::Color::White, "KQ"),
+            (chess_engine::Color::Black, "kq"),
+        ] {
+            let (kingside, queenside) = castling_rights(&self.0, color);
+            let mut sides = sides.chars();
+            castling.extend(sides.next().filter(|_| kingside));
+            castling.extend(sides.next().filter(|_| queenside));
+        }
+
+        let hash = ruby.hash_new();
+        hash.aset(Symbol::new("pieces"), pieces)?;
+        hash.aset(
+            Symbol::new("turn"),
+            codec::color_name(self.0.get_turn_color()),
+        )?;
+        hash.aset(Symbol::new("castling"), castling)?;
+        hash.aset(
+            Symbol::new("en_passant"),
+            self.0.get_en_passant().map(|pos| pos.to_string()),
+        )?;
+
+        Ok(hash)
+    }
+
+    /// Read a board back from `to_h`, going through the packed format so the same checks
+    /// apply.
+    fn from_h(hash: RHash) -> Result<Self, magnus::Error> {
+        let ruby = magnus::Ruby::get().unwrap();
+
+        let pieces = codec::field::<RArray>(hash, "pieces")?
+            .into_iter()
+            .map(|piece| codec::piece_from_h(RHash::try_convert(piece)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let turn = codec::parse_color(&codec::field::<String>(hash, "turn")?)?;
+
+        let castling: String = codec::field(hash, "castling")?;
+        let mut rights = [false; 4];
+        for c in castling.chars().filter(|&c| c != '-') {
+            let i = "KQkq".find(c).ok_or_else(|| {
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid castling rights {castling:?}"),
+                )
+            })?;
+            rights[i] = true;
+        }
+
+        let en_passant = codec::optional_field::<String>(hash, "en_passant")?
+            .map(|square| codec::parse_position(&square))
+            .transpose()?;
+
+        codec::pack_board(pieces, turn, rights, en_passant)
+            .and_then(|bytes| codec::decode_board(&bytes))
+            .map(Self)
+            .ok_or_else(|| {
+                magnus::Error::new(ruby.exception_arg_error(), "inconsistent board hash")
+            })
+    }
+
+    fn polyglot_key(&self) -> u64 {
+        polyglot::key(&self.0)
+    }
+
+    fn to_fen(&self) -> String {
+        fen::to_fen(&self.0)
+    }
+
+    fn to_svg(&self, args: &[Value]) -> Result<String, magnus::Error> {
+        let args = scan_args::<(), (), (), (), RHash, ()>(args)?;
+        let kwargs = get_kwargs::<
+            _,
+            (),
+            (
+                Option<u32>,
+                Option<bool>,
+                Option<Move>,
+                Option<RArray>,
+                Option<RArray>,
+                Option<bool>,
+            ),
+            (),
+        >(
+            args.keywords,
+            &[],
+            &[
+                "size",
+                "flipped",
+                "last_move",
+                "arrows",
+                "highlights",
+                "coordinates",
+            ],
+        )?;
+        let (size, flipped, last_move, arrows, highlights, coordinates) = kwargs.optional;
+        let defaults = svg::Options::default();
+
+        let position = |value: Value| -> Result<chess_engine::Position, magnus::Error> {
+            Ok(Position::try_convert(value)?.into())
+        };
+        let arrows = arrows
+            .into_iter()
+            .flatten()
+            .map(|value| {
+                let pair = RArray::try_convert(value)?;
+                Ok((position(pair.entry(0)?)?, position(pair.entry(1)?)?))
+            })
+            .collect::<Result<_, magnus::Error>>()?;
+        let highlights = highlights
+            .into_iter()
+            .flatten()
+            .map(position)
+            .collect::<Result<_, _>>()?;
+
+        let options = svg::Options {
+            size: size.unwrap_or(defaults.size),
+            flipped: flipped.unwrap_or(defaults.flipped),
+            last_move: last_move.and_then(|m| move_squares(&self.0, m.into())),
+            arrows,
+            highlights,
+            coordinates: coordinates.unwrap_or(defaults.coordinates),
+        };
+
+        Ok(svg::render(&self.0, &options))
+    }
+
+    fn render(&self, args: &[Value]) -> Result<String, magnus::Error> {
+        let args = scan_args::<(), (), (), (), RHash, ()>(args)?;
+        let kwargs = get_kwargs::<
+            _,
+            (),
+            (
+                Option<Symbol>,
+                Option<bool>,
+                Option<Color>,
+                Option<RArray>,
+                Option<Move>,
+            ),
+            (),
+        >(
+            args.keywords,
+            &[],
+            &["style", "color", "perspective", "highlight", "last_move"],
+        )?;
+        let (style, color, perspective, highlight, last_move) = kwargs.optional;
+
+        let style = match style.map(Symbol::name).transpose()?.as_deref() {
+            None | Some("unicode") => render::Style::Unicode,
+            Some("ascii") => render::Style::Ascii,
+            Some(other) => {
+                let ruby = magnus::Ruby::get().unwrap();
+                return Err(magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("unknown style :{other}, expected :unicode or :ascii"),
+                ));
+            }
+        };
+        let highlights = highlight
+            .into_iter()
+            .flatten()
+            .map(|value| Ok(Position::try_convert(value)?.into()))
+            .collect::<Result<_, magnus::Error>>()?;
+
+        let options = render::Options {
+            style,
+            color: color.unwrap_or(true),
+            perspective: perspective.map_or(chess_engine::Color::White, Into::into),
+            highlights,
+            last_move: last_move.and_then(|m| move_squares(&self.0, m.into())),
+        };
+
+        Ok(render::render(&self.0, &options))
     }
 
     fn to_string(&self) -> String {
@@ -177,7 +548,7 @@ impl Board {
 }
 
 impl Board {
-    fn value_for(&self, &color: &Color) -> f64 {
+    fn value_for(&self, &color: &Color) -> Result<f64, magnus::Error> {
         no_gvl!(self.0.value_for(color.into()))
     }
 
@@ -189,26 +560,224 @@ impl Board {
         self.0.apply_eval_move(m.into()).into()
     }
 
-    fn get_legal_moves(&self) -> Vec<Move> {
+    fn parse_move(&self, input: String) -> Result<Move, magnus::Error> {
+        // Like "Nce2, Nge2 or O-O".
+        let list = |moves: &[chess_engine::Move]| {
+            let mut sans = moves
+                .iter()
+                .map(|&m| notation::san(&self.0, m))
+                .collect::<Vec<_>>();
+            let last = sans.pop().unwrap_or_default();
+            if sans.is_empty() {
+                last
+            } else {
+                format!("{} or {last}", sans.join(", "))
+            }
+        };
+
+        match notation::resolve(&self.0, &input) {
+            Ok(m) => Ok(m.into()),
+            Err(Unresolved::Unreadable(err)) => Err(err.into()),
+            Err(Unresolved::Ambiguous(candidates)) => Err(errors::ambiguous_move(
+                format!("{input:?} could be {}", list(&candidates)),
+                &input,
+                &candidates,
+            )),
+            Err(Unresolved::Illegal(m, suggestions)) => {
+                let mut message = format!("{input:?} isn't a legal move");
+                if !suggestions.is_empty() {
+                    message.push_str(&format!(", did you mean {}?", list(&suggestions)));
+                }
+                Err(errors::suggest_moves(message, m, self.0, &suggestions))
+            }
+        }
+    }
+
+    fn checkers(&self, &color: &Color) -> Vec<Piece> {
+        attacks::checkers(&self.0, color.into())
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    fn pinned_pieces(&self, &color: &Color) -> Vec<Pin> {
+        attacks::pins(&self.0, color.into())
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// The pieces of `color`, by default the side to move, that would uncover an attack on
+    /// the other king, or on a piece worth more than the attacker or left undefended, by
+    /// moving out of the way.
+    fn discovered_attackers(&self, args: &[Value]) -> Result<Vec<DiscoveredAttack>, magnus::Error> {
+        let args = scan_args::<(), (Option<Color>,), (), (), (), ()>(args)?;
+        let (color,) = args.optional;
+        let color = color.map_or_else(|| self.0.get_turn_color(), Into::into);
+
+        Ok(attacks::discovered_attacks(&self.0, color)
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// The material `m` wins in pawns once every capture on its square is played out, with
+    /// each side stopping when recapturing would lose material. Negative if it loses
+    /// material, and 0 for castling and resigning.
+    /// Raises `ArgumentError` for moves the piece couldn't make even ignoring checks.
+    fn see(&self, &m: &Move) -> Result<i32, magnus::Error> {
+        let m = m.into();
+        if let Some(illegality) = no_gvl!(illegality::explain(&self.0, m))?
+            .filter(|illegality| !illegality.is_pseudo_legal())
+        {
+            let ruby = magnus::Ruby::get().unwrap();
+            return Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                illegality.message(),
+            ));
+        }
+
+        no_gvl!(attacks::see(&self.0, m))
+    }
+
+    fn see_ge(&self, &m: &Move, threshold: i32) -> Result<bool, magnus::Error> {
+        Ok(self.see(&m)? >= threshold)
+    }
+
+    fn pawn_structure(&self, &color: &Color) -> PawnStructure {
+        PawnStructure::new(&self.0, color.into())
+    }
+
+    fn illegality_reason(&self, &m: &Move) -> Result<Option<Illegality>, magnus::Error> {
+        no_gvl!(illegality::explain(&self.0, m.into()))
+    }
+
+    fn move_info(&self, &m: &Move) -> Result<MoveInfo, magnus::Error> {
+        let m = m.into();
+        if m == chess_engine::Move::Resign {
+            let ruby = magnus::Ruby::get().unwrap();
+            return Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                "resigning doesn't move a piece",
+            ));
+        }
+
+        no_gvl!(legal_moves(&self.0)
+            .contains(&m)
+            .then(|| MoveInfo::new(&self.0, m))
+            .flatten())?
+        .ok_or_else(|| errors::illegal_move(format!("illegal move {m}"), m, self.0))
+    }
+
+    fn get_legal_moves(&self) -> Result<Vec<Move>, magnus::Error> {
         no_gvl! {
-            self.0
-            .get_legal_moves()
+            legal_moves(&self.0)
             .into_iter()
             .map(Into::into)
             .collect()
         }
     }
 
-    fn get_best_next_move(&self, depth: i32) -> (Move, u64, f64) {
-        let (m, u, f) = no_gvl!(self.0.get_best_next_move(depth));
+    /// Search for the best move, either to a fixed `depth` or, given the mover's clock in
+    /// `wtime`/`btime`, for as long as the clock allows, with `depth` as a cap.
+    ///
+    /// Clock times and increments are in milliseconds, like UCI's `go` command.
+    fn get_best_next_move(&self, args: &[Value]) -> Result<(Move, u64, f64), magnus::Error> {
+        let args = scan_args::<(), (Option<i32>,), (), (), RHash, ()>(args)?;
+        let depth = args.optional.0.map(check_depth).transpose()?;
+        #[allow(clippy::type_complexity)]
+        let kwargs = get_kwargs::<
+            _,
+            (),
+            (
+                Option<Obj<OpeningBook>>,
+                Option<Obj<Tablebase>>,
+                Option<u64>,
+                Option<u64>,
+                Option<u64>,
+                Option<u64>,
+                Option<u32>,
+            ),
+            (),
+        >(
+            args.keywords,
+            &[],
+            &[
+                "book",
+                "tablebase",
+                "wtime",
+                "btime",
+                "winc",
+                "binc",
+                "movestogo",
+            ],
+        )?;
+        let (book, tablebase, wtime, btime, winc, binc, moves_to_go) = kwargs.optional;
+
+        // Book and tablebase moves are played without searching, so they evaluate no boards.
+        let mut known = match book {
+            Some(book) => book.pick_with(&self.0, None)?,
+            None => None,
+        };
+        if let (None, Some(tablebase)) = (known, tablebase) {
+            known = tablebase.best_move_for(&self.0)?;
+        }
+        if let Some(m) = known {
+            let color = self.0.get_current_player_color();
+            return Ok((m.into(), 0, self.0.apply_eval_move(m).value_for(color)));
+        }
+
+        let (time, increment) = match self.0.get_turn_color() {
+            chess_engine::Color::White => (wtime, winc),
+            chess_engine::Color::Black => (btime, binc),
+        };
+
+        let (m, u, f) = match (time, depth) {
+            (Some(time), depth) => {
+                let control = search::TimeControl {
+                    time: Duration::from_millis(time),
+                    increment: Duration::from_millis(increment.unwrap_or(0)),
+                    moves_to_go,
+                };
+                let budget = search::budget(&self.0, &control);
+                no_gvl!(search::search(&self.0, budget, depth.unwrap_or(64)))?
+            }
+            (None, Some(depth)) => no_gvl!(search::best_at_depth(&self.0, depth))?,
+            (None, None) => {
+                let ruby = magnus::Ruby::get().unwrap();
+                return Err(magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    "a depth or the side to move's clock time is required",
+                ));
+            }
+        };
+
+        Ok((m.into(), u, f))
+    }
+
+    fn find_mate(&self, max_moves: usize) -> Result<Option<Vec<Move>>, magnus::Error> {
+        check_mate_length(max_moves)?;
+        no_gvl! {
+            mate::find_mate(&self.0, max_moves)
+                .map(|line| line.into_iter().map(Into::into).collect())
+        }
+    }
 
-        (m.into(), u, f)
+    fn mating_moves(&self, max_moves: usize) -> Result<Vec<Move>, magnus::Error> {
+        check_mate_length(max_moves)?;
+        no_gvl! {
+            mate::mating_moves(&self.0, max_moves)
+                .into_iter()
+                .map(Into::into)
+                .collect()
+        }
     }
 
-    fn get_worst_next_move(&self, depth: i32) -> (Move, u64, f64) {
-        let (m, u, f) = no_gvl!(self.0.get_worst_next_move(depth));
+    fn get_worst_next_move(&self, depth: i32) -> Result<(Move, u64, f64), magnus::Error> {
+        let depth = check_depth(depth)?;
+        let (m, u, f) = no_gvl!(search::worst_at_depth(&self.0, depth))?;
 
-        (m.into(), u, f)
+        Ok((m.into(), u, f))
     }
 
     fn minimax(
@@ -219,7 +788,7 @@ impl Board {
         is_maximizing: bool,
         &getting_move_for: &Color,
         mut board_count: u64,
-    ) -> (f64, u64) {
+    ) -> Result<(f64, u64), magnus::Error> {
         no_gvl! {
             (
                 self.0.minimax(
@@ -260,6 +829,13 @@ pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Erro
     class.define_method("king_pos", method!(Board::get_king_pos, 1))?;
     class.define_method("threatened?", method!(Board::is_threatened, 2))?;
     class.define_method("in_check?", method!(Board::is_in_check, 1))?;
+    class.define_method("checkers", method!(Board::checkers, 1))?;
+    class.define_method("pinned_pieces", method!(Board::pinned_pieces, 1))?;
+    class.define_method("pawn_structure", method!(Board::pawn_structure, 1))?;
+    class.define_method(
+        "discovered_attackers",
+        method!(Board::discovered_attackers, -1),
+    )?;
     class.define_method(
         "can_kingside_castle?",
         method!(Board::can_kingside_castle, 1),
@@ -280,6 +856,7 @@ pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Erro
     class.define_method("checkmate?", method!(Board::is_checkmate, 0))?;
     class.define_method("change_turn", method!(Board::change_turn, 0))?;
     class.define_method("play_move", method!(Board::play_move, 1))?;
+    class.define_method("play_move!", method!(Board::play_move_checked, 1))?;
 
     class.define_method("value_for", method!(Board::value_for, 1))?;
     class.define_method(
@@ -288,9 +865,28 @@ pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Erro
     )?;
     class.define_method("apply_eval_move", method!(Board::apply_eval_move, 1))?;
     class.define_method("legal_moves", method!(Board::get_legal_moves, 0))?;
-    class.define_method("best_next_move", method!(Board::get_best_next_move, 1))?;
+    class.define_method("parse_move", method!(Board::parse_move, 1))?;
+    class.define_method("move_info", method!(Board::move_info, 1))?;
+    class.define_method("illegality_reason", method!(Board::illegality_reason, 1))?;
+    class.define_method("see", method!(Board::see, 1))?;
+    class.define_method("see_ge", method!(Board::see_ge, 2))?;
+    class.define_method("best_next_move", method!(Board::get_best_next_move, -1))?;
     class.define_method("worst_next_move", method!(Board::get_worst_next_move, 1))?;
     class.define_method("minimax", method!(Board::minimax, 6))?;
+    class.define_method("find_mate", method!(Board::find_mate, 1))?;
+    class.define_method("mating_moves", method!(Board::mating_moves, 1))?;
+
+    class.define_singleton_method("_load", function!(Board::load, 1))?;
+    class.define_singleton_method("from_h", function!(Board::from_h, 1))?;
+    class.define_method("_dump", method!(Board::dump, 1))?;
+    class.define_method("dup", method!(Board::dup, 0))?;
+    class.define_method("clone", method!(Board::dup, 0))?;
+    class.define_method("to_h", method!(Board::to_h, 0))?;
+
+    class.define_method("polyglot_key", method!(Board::polyglot_key, 0))?;
+    class.define_method("to_fen", method!(Board::to_fen, 0))?;
+    class.define_method("to_svg", method!(Board::to_svg, -1))?;
+    class.define_method("render", method!(Board::render, -1))?;
 
     class.define_method("inspect", method!(Board::inspect, 0))?;
     class.define_method("to_s", method!(Board::to_string, 0))?;
@@ -0,0 +1,140 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    value::ReprValue,
+    Module, Object, RHash, Value,
+};
+
+use crate::{board::Board, enums::Move, polyglot};
+
+#[derive(Clone, Copy)]
+struct Entry {
+    key: u64,
+    raw_move: u16,
+    weight: u16,
+}
+
+#[magnus::wrap(class = "Silverpoint::OpeningBook", size, free_immediately)]
+pub struct OpeningBook {
+    entries: Vec<Entry>,
+}
+
+impl OpeningBook {
+    fn open(path: String) -> Result<Self, magnus::Error> {
+        let ruby = magnus::Ruby::get().unwrap();
+        let bytes = std::fs::read(&path).map_err(|err| {
+            magnus::Error::new(ruby.exception_io_error(), format!("{path}: {err}"))
+        })?;
+
+        if bytes.len() % 16 != 0 {
+            return Err(magnus::Error::new(
+                ruby.exception_runtime_error(),
+                format!("{path} is not a polyglot book"),
+            ));
+        }
+
+        let mut entries = bytes
+            .chunks_exact(16)
+            .map(|entry| Entry {
+                key: u64::from_be_bytes(entry[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes([entry[8], entry[9]]),
+                weight: u16::from_be_bytes([entry[10], entry[11]]),
+            })
+            .collect::<Vec<_>>();
+        // Lookups binary search on the key, so sort in case the file isn't. The sort is stable
+        // so each position's moves keep their book order.
+        entries.sort_by_key(|entry| entry.key);
+
+        Ok(Self { entries })
+    }
+
+    /// All legal book moves for a board along with their weights, in book order.
+    pub fn lookup(&self, board: &chess_engine::Board) -> Vec<(chess_engine::Move, u16)> {
+        let key = polyglot::key(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+
+        self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| {
+                polyglot::decode_move(board, entry.raw_move).map(|m| (m, entry.weight))
+            })
+            .filter(|&(m, _)| {
+                !matches!(board.play_move(m), chess_engine::GameResult::IllegalMove(_))
+            })
+            .collect()
+    }
+
+    /// Pick a book move at random, weighted by the book's weights.
+    ///
+    /// `random` is anything that responds to `rand(n)`, defaulting to `Random`.
+    pub fn pick_with(
+        &self,
+        board: &chess_engine::Board,
+        random: Option<Value>,
+    ) -> Result<Option<chess_engine::Move>, magnus::Error> {
+        let moves = self.lookup(board);
+        let total: u64 = moves.iter().map(|&(_, weight)| u64::from(weight)).sum();
+        if total == 0 {
+            return Ok(None);
+        }
+
+        let ruby = magnus::Ruby::get().unwrap();
+        let mut choice: u64 = match random {
+            Some(random) => random.funcall("rand", (total,))?,
+            None => ruby.class_random().funcall("rand", (total,))?,
+        };
+
+        for (m, weight) in moves {
+            let weight = u64::from(weight);
+            if choice < weight {
+                return Ok(Some(m));
+            }
+            choice -= weight;
+        }
+
+        Ok(None)
+    }
+
+    fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn moves(&self, board: &Board) -> Vec<(Move, u16)> {
+        self.lookup(&(*board).into())
+            .into_iter()
+            .map(|(m, weight)| (m.into(), weight))
+            .collect()
+    }
+
+    fn pick(&self, args: &[Value]) -> Result<Option<Move>, magnus::Error> {
+        let args = scan_args::<(&Board,), (), (), (), RHash, ()>(args)?;
+        let (board,) = args.required;
+        let kwargs = get_kwargs::<_, (), (Option<Value>,), ()>(args.keywords, &[], &["random"])?;
+        let (random,) = kwargs.optional;
+
+        let m = self.pick_with(&(*board).into(), random)?;
+        Ok(m.map(Into::into))
+    }
+
+    fn includes(&self, board: &Board) -> bool {
+        !self.lookup(&(*board).into()).is_empty()
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("OpeningBook", ruby.class_object())?;
+    class.define_singleton_method("open", function!(OpeningBook::open, 1))?;
+
+    class.define_method("size", method!(OpeningBook::size, 0))?;
+    class.define_method("moves", method!(OpeningBook::moves, 1))?;
+    class.define_method("pick", method!(OpeningBook::pick, -1))?;
+    class.define_method("include?", method!(OpeningBook::includes, 1))?;
+
+    Ok(())
+}
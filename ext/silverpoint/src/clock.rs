@@ -0,0 +1,312 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    Module, Object, RHash, Value,
+};
+
+use crate::{
+    board::Board,
+    enums::{Color, GameResult, Reason},
+};
+
+#[derive(Clone, Copy)]
+struct Stage {
+    /// Moves to be made in this stage, or `None` for sudden death.
+    moves: Option<u32>,
+    time: Duration,
+    /// Fischer increment, added after every move.
+    increment: Duration,
+    /// Bronstein delay: time used up to this much is given back after every move.
+    delay: Duration,
+}
+
+/// Parse one stage like `40/90+30`, `5+3` or `15d10`.
+///
+/// Stage times are in minutes, increments and delays in seconds.
+fn parse_stage(stage: &str) -> Option<Stage> {
+    let (moves, rest) = match stage.split_once('/') {
+        Some((moves, rest)) => (Some(moves.trim().parse().ok()?), rest),
+        None => (None, stage),
+    };
+
+    let seconds = |s: &str| {
+        let s = s.trim().parse::<f64>().ok()?;
+        (s.is_finite() && s >= 0.0).then(|| Duration::from_secs_f64(s))
+    };
+
+    let (time, increment, delay) = if let Some((time, increment)) = rest.split_once('+') {
+        (time, seconds(increment)?, Duration::ZERO)
+    } else if let Some((time, delay)) = rest.split_once('d') {
+        (time, Duration::ZERO, seconds(delay)?)
+    } else {
+        (rest, Duration::ZERO, Duration::ZERO)
+    };
+
+    Some(Stage {
+        moves,
+        time: seconds(time)? * 60,
+        increment,
+        delay,
+    })
+}
+
+struct State {
+    stages: Vec<Stage>,
+    remaining: [Duration; 2],
+    /// The stage each player is in, and the moves they have made in it.
+    stage: [usize; 2],
+    moves: [u32; 2],
+    /// Moves each player has made in total.
+    played: [u32; 2],
+    turn: chess_engine::Color,
+    /// When the running clock was started, or `None` while the clock is stopped.
+    started: Option<Instant>,
+    flagged: Option<chess_engine::Color>,
+}
+
+fn index(color: chess_engine::Color) -> usize {
+    match color {
+        chess_engine::Color::White => 0,
+        chess_engine::Color::Black => 1,
+    }
+}
+
+impl State {
+    fn new(stages: Vec<Stage>) -> Self {
+        let time = stages[0].time;
+
+        Self {
+            stages,
+            remaining: [time; 2],
+            stage: [0; 2],
+            moves: [0; 2],
+            played: [0; 2],
+            turn: chess_engine::Color::White,
+            started: None,
+            flagged: None,
+        }
+    }
+
+    fn remaining(&self, color: chess_engine::Color, now: Instant) -> Duration {
+        let remaining = self.remaining[index(color)];
+
+        match self.started {
+            Some(started) if color == self.turn => {
+                remaining.saturating_sub(now.saturating_duration_since(started))
+            }
+            _ => remaining,
+        }
+    }
+
+    /// Record a flag fall for the running clock, if it has run out.
+    fn check_flag(&mut self, now: Instant) -> Option<chess_engine::Color> {
+        if self.flagged.is_none() && self.remaining(self.turn, now).is_zero() {
+            self.flagged = Some(self.turn);
+            self.remaining[index(self.turn)] = Duration::ZERO;
+            self.started = None;
+        }
+
+        self.flagged
+    }
+
+    fn press(&mut self, now: Instant) {
+        if self.check_flag(now).is_some() {
+            return;
+        }
+        let Some(started) = self.started else {
+            // Pressing a stopped clock starts it for the side to move.
+            self.started = Some(now);
+            return;
+        };
+
+        let player = index(self.turn);
+        let used = now.saturating_duration_since(started);
+        let stage = self.stages[self.stage[player]];
+
+        self.remaining[player] =
+            self.remaining[player].saturating_sub(used) + stage.increment + used.min(stage.delay);
+        self.moves[player] += 1;
+        self.played[player] += 1;
+
+        // Completing a stage moves on to the next one, repeating the last one if it has a
+        // move count of its own.
+        if stage.moves == Some(self.moves[player]) {
+            let next = (self.stage[player] + 1).min(self.stages.len() - 1);
+            self.stage[player] = next;
+            self.moves[player] = 0;
+            self.remaining[player] += self.stages[next].time;
+        }
+
+        self.turn = !self.turn;
+        self.started = Some(now);
+    }
+}
+
+#[magnus::wrap(class = "Silverpoint::Clock", size, free_immediately)]
+pub struct Clock(RefCell<State>);
+
+impl Clock {
+    /// A sudden death clock of `time` seconds each, with an optional `increment:` or Bronstein
+    /// `delay:`, also in seconds.
+    fn new(args: &[Value]) -> Result<Self, magnus::Error> {
+        let args = scan_args::<(f64,), (), (), (), RHash, ()>(args)?;
+        let (time,) = args.required;
+        let kwargs = get_kwargs::<_, (), (Option<f64>, Option<f64>), ()>(
+            args.keywords,
+            &[],
+            &["increment", "delay"],
+        )?;
+        let (increment, delay) = kwargs.optional;
+
+        let ruby = magnus::Ruby::get().unwrap();
+        let duration = |seconds: f64| {
+            Duration::try_from_secs_f64(seconds).map_err(|err| {
+                magnus::Error::new(ruby.exception_arg_error(), format!("{seconds}: {err}"))
+            })
+        };
+
+        Ok(Self(RefCell::new(State::new(vec![Stage {
+            moves: None,
+            time: duration(time)?,
+            increment: duration(increment.unwrap_or(0.0))?,
+            delay: duration(delay.unwrap_or(0.0))?,
+        }]))))
+    }
+
+    /// A clock for a time control of comma-separated stages, each read by `parse_stage`.
+    ///
+    /// Base times are in minutes, as time controls are written, unlike `new`'s seconds.
+    fn parse(control: String) -> Result<Self, magnus::Error> {
+        let stages = control
+            .split(',')
+            .map(parse_stage)
+            .collect::<Option<Vec<_>>>()
+            .filter(|stages| !stages.is_empty())
+            .ok_or_else(|| {
+                let ruby = magnus::Ruby::get().unwrap();
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid time control {control:?}"),
+                )
+            })?;
+
+        Ok(Self(RefCell::new(State::new(stages))))
+    }
+
+    fn start(&self) {
+        let mut state = self.0.borrow_mut();
+        if state.started.is_none() && state.flagged.is_none() {
+            state.started = Some(Instant::now());
+        }
+    }
+
+    fn stop(&self) {
+        let now = Instant::now();
+        let mut state = self.0.borrow_mut();
+        if state.check_flag(now).is_some() {
+            return;
+        }
+
+        if let Some(started) = state.started.take() {
+            let player = index(state.turn);
+            let used = now.saturating_duration_since(started);
+            state.remaining[player] = state.remaining[player].saturating_sub(used);
+        }
+    }
+
+    fn press(&self) {
+        self.0.borrow_mut().press(Instant::now());
+    }
+
+    fn is_running(&self) -> bool {
+        self.0.borrow().started.is_some()
+    }
+
+    fn turn(&self) -> Color {
+        self.0.borrow().turn.into()
+    }
+
+    /// Seconds left on `color`'s clock.
+    fn remaining(&self, &color: &Color) -> f64 {
+        self.0
+            .borrow()
+            .remaining(color.into(), Instant::now())
+            .as_secs_f64()
+    }
+
+    fn moves(&self, &color: &Color) -> u32 {
+        self.0.borrow().played[index(color.into())]
+    }
+
+    fn is_flag(&self) -> bool {
+        self.0.borrow_mut().check_flag(Instant::now()).is_some()
+    }
+
+    fn flagged(&self) -> Option<Color> {
+        self.0
+            .borrow_mut()
+            .check_flag(Instant::now())
+            .map(Into::into)
+    }
+
+    /// The result of a flag fall on `board`, or `nil` while both players have time.
+    ///
+    /// Running out of time loses, unless the opponent couldn't mate with any series of legal
+    /// moves, in which case it's a draw.
+    fn result(&self, board: &Board) -> Option<GameResult> {
+        let flagged = self.0.borrow_mut().check_flag(Instant::now())?;
+        let board = chess_engine::Board::from(*board);
+
+        let result = if board.has_insufficient_material(!flagged) {
+            GameResult::draw(Reason::Timeout)
+        } else {
+            GameResult::victory(!flagged, Reason::Timeout)
+        };
+        Some(result.on(board, None))
+    }
+
+    fn inspect(&self) -> String {
+        let now = Instant::now();
+        let state = self.0.borrow();
+
+        format!(
+            "#<Silverpoint::Clock white={:.1} black={:.1}>",
+            state
+                .remaining(chess_engine::Color::White, now)
+                .as_secs_f64(),
+            state
+                .remaining(chess_engine::Color::Black, now)
+                .as_secs_f64(),
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("Clock", ruby.class_object())?;
+    class.define_singleton_method("new", function!(Clock::new, -1))?;
+    class.define_singleton_method("parse", function!(Clock::parse, 1))?;
+
+    class.define_method("start", method!(Clock::start, 0))?;
+    class.define_method("stop", method!(Clock::stop, 0))?;
+    class.define_method("press", method!(Clock::press, 0))?;
+    class.define_method("running?", method!(Clock::is_running, 0))?;
+    class.define_method("turn", method!(Clock::turn, 0))?;
+    class.define_method("remaining", method!(Clock::remaining, 1))?;
+    class.define_method("moves", method!(Clock::moves, 1))?;
+    class.define_method("flag?", method!(Clock::is_flag, 0))?;
+    class.define_method("flagged", method!(Clock::flagged, 0))?;
+    class.define_method("result", method!(Clock::result, 1))?;
+    class.define_method("inspect", method!(Clock::inspect, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::{BoardBuilder, Color, Evaluate, Move, Piece, Position};
+use magnus::{RHash, RString, Symbol, TryConvert};
+
+use crate::{
+    board::castling_rights,
+    position::{from_square_index, parse_square, square_index},
+};
+
+/// A packed board: a nibble per square, then a flags byte and the en passant square.
+pub const BOARD_LEN: usize = 34;
+
+const BLACK_TO_MOVE: u8 = 1;
+const WHITE_KINGSIDE: u8 = 1 << 1;
+const WHITE_QUEENSIDE: u8 = 1 << 2;
+const BLACK_KINGSIDE: u8 = 1 << 3;
+const BLACK_QUEENSIDE: u8 = 1 << 4;
+const EN_PASSANT: u8 = 1 << 5;
+
+/// Move flags, kept in the top 4 bits of an encoded move.
+const KINGSIDE_CASTLE: u16 = 1 << 12;
+const QUEENSIDE_CASTLE: u16 = 2 << 12;
+const RESIGN: u16 = 3 << 12;
+
+/// Pieces are numbered pawn to king from 1 for white and from 9 for black, leaving 0 empty.
+pub fn piece_code(piece: Piece) -> u8 {
+    let kind = match piece {
+        Piece::Pawn(..) => 1,
+        Piece::Knight(..) => 2,
+        Piece::Bishop(..) => 3,
+        Piece::Rook(..) => 4,
+        Piece::Queen(..) => 5,
+        Piece::King(..) => 6,
+    };
+
+    match piece.get_color() {
+        Color::White => kind,
+        Color::Black => kind | 8,
+    }
+}
+
+pub fn piece_from_code(code: u8, pos: Position) -> Option<Piece> {
+    let color = if code & 8 == 0 {
+        Color::White
+    } else {
+        Color::Black
+    };
+
+    Some(match code & 7 {
+        1 => Piece::Pawn(color, pos),
+        2 => Piece::Knight(color, pos),
+        3 => Piece::Bishop(color, pos),
+        4 => Piece::Rook(color, pos),
+        5 => Piece::Queen(color, pos),
+        6 => Piece::King(color, pos),
+        _ => return None,
+    })
+}
+
+fn square_byte(pos: Position) -> Option<u8> {
+    pos.is_on_board()
+        .then(|| u8::try_from(square_index(pos)).expect("square index fits in a byte"))
+}
+
+pub fn encode_position(pos: Position) -> Option<u8> {
+    square_byte(pos)
+}
+
+pub fn decode_position(byte: u8) -> Option<Position> {
+    (byte < 64).then(|| from_square_index(usize::from(byte)))
+}
+
+/// Encode a move in 16 bits: the destination square in the low 6, the origin in the next 6
+/// and castling or resigning in the top 4.
+pub fn encode_move(m: Move) -> Option<u16> {
+    match m {
+        Move::Piece(from, to) => {
+            Some(u16::from(square_byte(from)?) << 6 | u16::from(square_byte(to)?))
+        }
+        Move::KingSideCastle => Some(KINGSIDE_CASTLE),
+        Move::QueenSideCastle => Some(QUEENSIDE_CASTLE),
+        Move::Resign => Some(RESIGN),
+    }
+}
+
+pub fn decode_move(raw: u16) -> Option<Move> {
+    match raw & 0xf000 {
+        0 => Some(Move::Piece(
+            from_square_index(usize::from(raw >> 6 & 63)),
+            from_square_index(usize::from(raw & 63)),
+        )),
+        KINGSIDE_CASTLE => Some(Move::KingSideCastle),
+        QUEENSIDE_CASTLE => Some(Move::QueenSideCastle),
+        RESIGN => Some(Move::Resign),
+        _ => None,
+    }
+}
+
+/// Pack a board from its parts. `rights` are the castling rights in FEN order: white
+/// kingside, white queenside, black kingside then black queenside.
+///
+/// Returns `None` if a piece or the en passant square is off the board.
+pub fn pack_board(
+    pieces: impl IntoIterator<Item = Piece>,
+    turn: Color,
+    rights: [bool; 4],
+    en_passant: Option<Position>,
+) -> Option<[u8; BOARD_LEN]> {
+    let mut bytes = [0; BOARD_LEN];
+
+    for piece in pieces {
+        let index = usize::from(square_byte(piece.get_pos())?);
+        bytes[index / 2] |= piece_code(piece) << (index % 2 * 4);
+    }
+
+    let flags = [
+        (turn == Color::Black, BLACK_TO_MOVE),
+        (rights[0], WHITE_KINGSIDE),
+        (rights[1], WHITE_QUEENSIDE),
+        (rights[2], BLACK_KINGSIDE),
+        (rights[3], BLACK_QUEENSIDE),
+        (en_passant.is_some(), EN_PASSANT),
+    ];
+    bytes[32] = flags
+        .into_iter()
+        .filter(|&(set, _)| set)
+        .fold(0, |flags, (_, flag)| flags | flag);
+    if let Some(en_passant) = en_passant {
+        bytes[33] = square_byte(en_passant)?;
+    }
+
+    Some(bytes)
+}
+
+pub fn encode_board(board: &chess_engine::Board) -> [u8; BOARD_LEN] {
+    let (white_kingside, white_queenside) = castling_rights(board, Color::White);
+    let (black_kingside, black_queenside) = castling_rights(board, Color::Black);
+
+    pack_board(
+        (0..64).filter_map(|index| board.get_piece(from_square_index(index))),
+        board.get_turn_color(),
+        [
+            white_kingside,
+            white_queenside,
+            black_kingside,
+            black_queenside,
+        ],
+        board.get_en_passant(),
+    )
+    .expect("board squares are on the board")
+}
+
+pub fn decode_board(bytes: &[u8]) -> Option<chess_engine::Board> {
+    let bytes: &[u8; BOARD_LEN] = bytes.try_into().ok()?;
+    let code_at = |index: usize| bytes[index / 2] >> (index % 2 * 4) & 15;
+    let flags = bytes[32];
+    let turn = if flags & BLACK_TO_MOVE == 0 {
+        Color::White
+    } else {
+        Color::Black
+    };
+
+    // `chess_engine` only sets the en passant square when a pawn moves two squares, so the
+    // pawn is put back and the move played again.
+    let double_push = if flags & EN_PASSANT == 0 {
+        None
+    } else {
+        let en_passant = decode_position(bytes[33])?;
+        let mover = !turn;
+        let row = match mover {
+            Color::White => 2,
+            Color::Black => 5,
+        };
+        if en_passant.get_row() != row {
+            return None;
+        }
+
+        let (from, to) = (en_passant.pawn_back(mover), en_passant.pawn_up(mover));
+        if code_at(square_index(to)) != piece_code(Piece::Pawn(mover, to))
+            || code_at(square_index(en_passant)) != 0
+            || code_at(square_index(from)) != 0
+        {
+            return None;
+        }
+        Some((mover, from, to))
+    };
+
+    let mut builder = BoardBuilder::default();
+    for index in 0..64 {
+        let code = code_at(index);
+        let pos = from_square_index(index);
+        if code == 0 || matches!(double_push, Some((_, _, to)) if to == pos) {
+            continue;
+        }
+        builder = builder.piece(piece_from_code(code, pos)?);
+    }
+    if let Some((mover, from, _)) = double_push {
+        builder = builder.piece(Piece::Pawn(mover, from));
+    }
+
+    let castles = [
+        (WHITE_KINGSIDE, Color::White, true),
+        (WHITE_QUEENSIDE, Color::White, false),
+        (BLACK_KINGSIDE, Color::Black, true),
+        (BLACK_QUEENSIDE, Color::Black, false),
+    ];
+    for (flag, color, kingside) in castles {
+        if flags & flag != 0 {
+            builder = if kingside {
+                builder.enable_kingside_castle(color)
+            } else {
+                builder.enable_queenside_castle(color)
+            };
+        }
+    }
+    let board = builder.build();
+
+    Some(match double_push {
+        None => board.set_turn(turn),
+        Some((mover, from, to)) => board.set_turn(mover).apply_eval_move(Move::Piece(from, to)),
+    })
+}
+
+pub fn string_bytes(str: RString) -> Vec<u8> {
+    // SAFETY: the bytes are copied before Ruby gets a chance to change the string.
+    unsafe { str.as_slice() }.to_vec()
+}
+
+pub fn binary_string(bytes: &[u8]) -> RString {
+    magnus::Ruby::get().unwrap().str_from_slice(bytes)
+}
+
+pub fn invalid_dump(class: &str) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    magnus::Error::new(
+        ruby.exception_arg_error(),
+        format!("invalid Silverpoint::{class} dump"),
+    )
+}
+
+/// Look up `key` in a hash, by symbol as `to_h` writes it or by string as JSON reads it back.
+pub fn field<T: TryConvert>(hash: RHash, key: &str) -> Result<T, magnus::Error> {
+    let value = hash
+        .get(Symbol::new(key))
+        .or_else(|| hash.get(key))
+        .ok_or_else(|| {
+            let ruby = magnus::Ruby::get().unwrap();
+            magnus::Error::new(ruby.exception_arg_error(), format!("missing key {key}"))
+        })?;
+
+    T::try_convert(value)
+}
+
+/// Like [`field`], but a missing key reads as `nil`.
+pub fn optional_field<T: TryConvert>(hash: RHash, key: &str) -> Result<Option<T>, magnus::Error> {
+    match hash.get(Symbol::new(key)).or_else(|| hash.get(key)) {
+        Some(value) => Option::<T>::try_convert(value),
+        None => Ok(None),
+    }
+}
+
+pub fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+pub fn parse_color(name: &str) -> Result<Color, magnus::Error> {
+    match name {
+        "white" => Ok(Color::White),
+        "black" => Ok(Color::Black),
+        _ => {
+            let ruby = magnus::Ruby::get().unwrap();
+            Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                format!("unknown color {name:?}"),
+            ))
+        }
+    }
+}
+
+pub fn parse_position(square: &str) -> Result<Position, magnus::Error> {
+    parse_square(square, 0, square.chars().count())
+}
+
+pub fn off_board(pos: Position) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    magnus::Error::new(
+        ruby.exception_arg_error(),
+        format!("can't encode off board position {pos:?}"),
+    )
+}
+
+pub fn off_board_move(m: Move) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    magnus::Error::new(
+        ruby.exception_arg_error(),
+        format!("can't encode off board move {m:?}"),
+    )
+}
+
+pub fn piece_to_h(piece: Piece) -> Result<RHash, magnus::Error> {
+    let hash = magnus::Ruby::get().unwrap().hash_new();
+    hash.aset(Symbol::new("name"), piece.get_name())?;
+    hash.aset(Symbol::new("color"), color_name(piece.get_color()))?;
+    hash.aset(Symbol::new("position"), piece.get_pos().to_string())?;
+
+    Ok(hash)
+}
+
+pub fn piece_from_h(hash: RHash) -> Result<Piece, magnus::Error> {
+    let name: String = field(hash, "name")?;
+    let color = parse_color(&field::<String>(hash, "color")?)?;
+    let pos = parse_position(&field::<String>(hash, "position")?)?;
+
+    Ok(match name.as_str() {
+        "pawn" => Piece::Pawn(color, pos),
+        "knight" => Piece::Knight(color, pos),
+        "bishop" => Piece::Bishop(color, pos),
+        "rook" => Piece::Rook(color, pos),
+        "queen" => Piece::Queen(color, pos),
+        "king" => Piece::King(color, pos),
+        _ => {
+            let ruby = magnus::Ruby::get().unwrap();
+            return Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                format!("unknown piece {name:?}"),
+            ));
+        }
+    })
+}
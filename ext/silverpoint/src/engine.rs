@@ -0,0 +1,200 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::cell::Cell;
+
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    value::ReprValue,
+    Module, Object, RHash, Value,
+};
+
+use crate::{
+    board::{ranked_moves, Board},
+    enums::Move,
+    gvl::no_gvl,
+};
+
+const MAX_SKILL: u8 = 20;
+
+/// The rating of skill level 0, and how much each level above it adds up to level 20.
+const MIN_ELO: u32 = 800;
+const ELO_PER_LEVEL: u32 = 60;
+const MAX_ELO: u32 = 2000;
+
+/// How many of the top moves a weakened engine chooses between, besides blunders.
+const CANDIDATES: usize = 4;
+
+fn depth(level: u8) -> i32 {
+    i32::from(level / 5)
+}
+
+fn blunder_chance(level: u8) -> f64 {
+    f64::from(MAX_SKILL - level).powi(2) / 2000.0
+}
+
+/// Like Stockfish's skill levels, each of the top few moves gets a random bonus that grows
+/// with how weak the level is, and the move with the best bonus score is played. Lower levels
+/// also sometimes blunder, though small losses are still more likely than big ones.
+fn pick(
+    ranked: &[(chess_engine::Move, f64)],
+    level: u8,
+    mut random: impl FnMut() -> Result<f64, magnus::Error>,
+) -> Result<chess_engine::Move, magnus::Error> {
+    let Some(&(best, top)) = ranked.first() else {
+        return Ok(chess_engine::Move::Resign);
+    };
+    if level >= MAX_SKILL {
+        return Ok(best);
+    }
+
+    if random()? < blunder_chance(level) {
+        let weights = ranked
+            .iter()
+            .map(|&(m, score)| (m, 1.0 / (10.0 + top - score)))
+            .collect::<Vec<_>>();
+        let mut choice = random()? * weights.iter().map(|&(_, w)| w).sum::<f64>();
+
+        for &(m, weight) in &weights {
+            if choice < weight {
+                return Ok(m);
+            }
+            choice -= weight;
+        }
+        return Ok(best);
+    }
+
+    let candidates = &ranked[..ranked.len().min(CANDIDATES)];
+    let weakness = f64::from(120 - 2 * u32::from(level));
+    // A pawn is worth 10, and the noise never swings more than a pawn's worth.
+    let delta = candidates
+        .last()
+        .map_or(0.0, |&(_, worst)| (top - worst).min(10.0));
+
+    let mut chosen = (best, f64::NEG_INFINITY);
+    for &(m, score) in candidates {
+        let push = (weakness * (top - score) + delta * weakness * random()?) / 128.0;
+        if score + push > chosen.1 {
+            chosen = (m, score + push);
+        }
+    }
+
+    Ok(chosen.0)
+}
+
+#[magnus::wrap(class = "Silverpoint::Engine", size, free_immediately)]
+pub struct Engine {
+    level: Cell<u8>,
+}
+
+impl Engine {
+    fn new(args: &[Value]) -> Result<Self, magnus::Error> {
+        let args = scan_args::<(), (), (), (), RHash, ()>(args)?;
+        let kwargs = get_kwargs::<_, (), (Option<i64>, Option<i64>), ()>(
+            args.keywords,
+            &[],
+            &["skill_level", "elo"],
+        )?;
+        let (level, elo) = kwargs.optional;
+
+        let engine = Self {
+            level: Cell::new(MAX_SKILL),
+        };
+        if let Some(level) = level {
+            engine.set_skill_level(level)?;
+        }
+        if let Some(elo) = elo {
+            engine.set_elo(elo)?;
+        }
+
+        Ok(engine)
+    }
+
+    fn skill_level(&self) -> u8 {
+        self.level.get()
+    }
+
+    fn set_skill_level(&self, level: i64) -> Result<(), magnus::Error> {
+        let level = u8::try_from(level)
+            .ok()
+            .filter(|&level| level <= MAX_SKILL)
+            .ok_or_else(|| {
+                let ruby = magnus::Ruby::get().unwrap();
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("skill level {level} is not in 0..{MAX_SKILL}"),
+                )
+            })?;
+
+        self.level.set(level);
+        Ok(())
+    }
+
+    fn elo(&self) -> u32 {
+        MIN_ELO + ELO_PER_LEVEL * u32::from(self.level.get())
+    }
+
+    /// Set the skill level nearest to a rating, rounding down.
+    fn set_elo(&self, elo: i64) -> Result<(), magnus::Error> {
+        let elo = u32::try_from(elo)
+            .ok()
+            .filter(|elo| (MIN_ELO..=MAX_ELO).contains(elo))
+            .ok_or_else(|| {
+                let ruby = magnus::Ruby::get().unwrap();
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("elo {elo} is not in {MIN_ELO}..{MAX_ELO}"),
+                )
+            })?;
+
+        self.set_skill_level(i64::from((elo - MIN_ELO) / ELO_PER_LEVEL))
+    }
+
+    /// Pick a move for the current player at the engine's skill level.
+    ///
+    /// `random` is anything that responds to `rand`, defaulting to `Random`.
+    fn best_move(&self, args: &[Value]) -> Result<Move, magnus::Error> {
+        let args = scan_args::<(&Board,), (), (), (), RHash, ()>(args)?;
+        let (board,) = args.required;
+        let kwargs = get_kwargs::<_, (), (Option<Value>,), ()>(args.keywords, &[], &["random"])?;
+        let (random,) = kwargs.optional;
+
+        let ruby = magnus::Ruby::get().unwrap();
+        let random = random.unwrap_or_else(|| ruby.class_random().as_value());
+
+        let level = self.level.get();
+        let board = (*board).into();
+        let ranked = no_gvl!(ranked_moves(&board, depth(level)))?;
+
+        pick(&ranked, level, || random.funcall("rand", ())).map(Into::into)
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::Engine skill_level={} elo={}>",
+            self.skill_level(),
+            self.elo()
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("Engine", ruby.class_object())?;
+    class.define_singleton_method("new", function!(Engine::new, -1))?;
+
+    class.define_method("skill_level", method!(Engine::skill_level, 0))?;
+    class.define_method("skill_level=", method!(Engine::set_skill_level, 1))?;
+    class.define_method("elo", method!(Engine::elo, 0))?;
+    class.define_method("elo=", method!(Engine::set_elo, 1))?;
+    class.define_method("best_move", method!(Engine::best_move, -1))?;
+    class.define_method("inspect", method!(Engine::inspect, 0))?;
+
+    class.const_set("MAX_SKILL_LEVEL", MAX_SKILL)?;
+    class.const_set("MIN_ELO", MIN_ELO)?;
+    class.const_set("MAX_ELO", MAX_ELO)?;
+
+    Ok(())
+}
@@ -3,15 +3,26 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use magnus::{function, method, Module, Object};
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    Module, Object, RHash, RString, Symbol, Value,
+};
+
+use crate::{
+    board::Board,
+    codec,
+    errors::ParseError,
+    frozen::frozen_wrap,
+    position::{parse_square, Position},
+};
 
-use crate::{board::Board, position::Position};
-
-#[magnus::wrap(class = "Silverpoint::Color", size, free_immediately)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Color(chess_engine::Color);
 
+frozen_wrap!(Color, "Silverpoint::Color");
+
 impl From<chess_engine::Color> for Color {
     fn from(value: chess_engine::Color) -> Self {
         Self(value)
@@ -25,6 +36,22 @@ impl From<Color> for chess_engine::Color {
 }
 
 impl Color {
+    fn dump(&self, _level: i32) -> RString {
+        codec::binary_string(&[u8::from(self.0 == chess_engine::Color::Black)])
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        match codec::string_bytes(dump)[..] {
+            [0] => Ok(Self(chess_engine::Color::White)),
+            [1] => Ok(Self(chess_engine::Color::Black)),
+            _ => Err(codec::invalid_dump("Color")),
+        }
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
     fn to_string(&self) -> String {
         format!("{}", self.0)
     }
@@ -34,11 +61,12 @@ impl Color {
     }
 }
 
-#[magnus::wrap(class = "Silverpoint::Piece", size, free_immediately)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Piece(chess_engine::Piece);
 
+frozen_wrap!(Piece, "Silverpoint::Piece");
+
 impl From<chess_engine::Piece> for Piece {
     fn from(value: chess_engine::Piece) -> Self {
         Self(value)
@@ -112,6 +140,36 @@ impl Piece {
         self.0.move_to(position.into()).into()
     }
 
+    /// Dump as two bytes: the piece's code, then its square.
+    fn dump(&self, _level: i32) -> Result<RString, magnus::Error> {
+        let pos = self.0.get_pos();
+        let square = codec::encode_position(pos).ok_or_else(|| codec::off_board(pos))?;
+
+        Ok(codec::binary_string(&[codec::piece_code(self.0), square]))
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        match codec::string_bytes(dump)[..] {
+            [code, square] => codec::decode_position(square)
+                .and_then(|pos| codec::piece_from_code(code, pos))
+                .map(Self)
+                .ok_or_else(|| codec::invalid_dump("Piece")),
+            _ => Err(codec::invalid_dump("Piece")),
+        }
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn to_h(&self) -> Result<RHash, magnus::Error> {
+        codec::piece_to_h(self.0)
+    }
+
+    fn from_h(hash: RHash) -> Result<Self, magnus::Error> {
+        codec::piece_from_h(hash).map(Self)
+    }
+
     fn to_string(&self) -> String {
         format!("{}", self.0)
     }
@@ -121,11 +179,49 @@ impl Piece {
     }
 }
 
-#[magnus::wrap(class = "Silverpoint::Move", size, free_immediately)]
+/// Find where `chess_engine::Move::parse` gave up on `str`, which it doesn't say.
+fn move_parse_error(str: &str) -> magnus::Error {
+    // Each word with the character column it starts at.
+    let mut words = Vec::new();
+    let mut start = None;
+    for (column, c) in str.chars().chain([' ']).enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(column),
+            (Some(from), true) => {
+                words.push((from, column - from));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let squares = match words[..] {
+        // Squares run together, like `e2e4`.
+        [(column, 4)] => parse_square(str, column, 2).and(parse_square(str, column + 2, 2)),
+        [(from, from_len), (to, to_len)] => {
+            parse_square(str, from, from_len).and(parse_square(str, to, to_len))
+        }
+        [(from, from_len), (to, 2), (column, len)]
+            if str.chars().skip(to).take(2).eq("to".chars()) =>
+        {
+            parse_square(str, from, from_len).and(parse_square(str, column, len))
+        }
+        [_, (to, _), _] => return ParseError::new(str, to, "\"to\"").into(),
+        [(column, _), ..] => return ParseError::new(str, column, "move").into(),
+        [] => return ParseError::new(str, str.chars().count(), "move").into(),
+    };
+
+    squares
+        .err()
+        .unwrap_or_else(|| ParseError::new(str, 0, "move").into())
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Move(chess_engine::Move);
 
+frozen_wrap!(Move, "Silverpoint::Move");
+
 unsafe impl magnus::IntoValueFromNative for Move {}
 
 impl From<chess_engine::Move> for Move {
@@ -140,6 +236,18 @@ impl From<Move> for chess_engine::Move {
     }
 }
 
+fn promotes(
+    board: &chess_engine::Board,
+    from: chess_engine::Position,
+    to: chess_engine::Position,
+) -> bool {
+    match board.get_piece(from) {
+        Some(chess_engine::Piece::Pawn(chess_engine::Color::White, _)) => to.get_row() == 7,
+        Some(chess_engine::Piece::Pawn(chess_engine::Color::Black, _)) => to.get_row() == 0,
+        _ => false,
+    }
+}
+
 impl Move {
     fn new_queenside_castle() -> Self {
         Self(chess_engine::Move::QueenSideCastle)
@@ -158,12 +266,92 @@ impl Move {
     }
 
     fn parse(str: String) -> Result<Self, magnus::Error> {
-        chess_engine::Move::parse(str)
-            .map(Into::into)
-            .map_err(|err| {
-                let ruby = magnus::Ruby::get().unwrap();
-                magnus::Error::new(ruby.exception_runtime_error(), err)
-            })
+        chess_engine::Move::parse(str.clone())
+            .map(Self)
+            .map_err(|_| move_parse_error(&str))
+    }
+
+    /// Read a move in UCI's long algebraic notation, like `e2e4`, `e7e8q` or `e1g1`.
+    ///
+    /// Castling can be written as the king moving two squares or, as in Chess960, taking its
+    /// own rook. `chess_engine` only promotes to queens, so underpromotions don't parse.
+    fn from_uci(str: String, board: &Board) -> Result<Self, magnus::Error> {
+        let board: chess_engine::Board = (*board).into();
+        let from = parse_square(&str, 0, 2)?;
+        let to = parse_square(&str, 2, 2)?;
+
+        let promotes = promotes(&board, from, to);
+        match str.chars().nth(4) {
+            None => {}
+            Some('q') if promotes => {}
+            Some(_) if promotes => {
+                return Err(ParseError::new(&str, 4, "promotion to a queen").into())
+            }
+            Some(_) => return Err(ParseError::new(&str, 4, "end of move").into()),
+        }
+        if str.chars().count() > 5 {
+            return Err(ParseError::new(&str, 5, "end of move").into());
+        }
+
+        if let Some(chess_engine::Piece::King(color, _)) = board.get_piece(from) {
+            let king = chess_engine::Position::king_pos(color);
+            if from == king && to.get_row() == king.get_row() {
+                match to.get_col() {
+                    6 | 7 => return Ok(Self(chess_engine::Move::KingSideCastle)),
+                    2 | 0 => return Ok(Self(chess_engine::Move::QueenSideCastle)),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self(chess_engine::Move::Piece(from, to)))
+    }
+
+    /// Write the move in UCI's long algebraic notation.
+    ///
+    /// Castling and promotion depend on who is moving what, so they need the `board` the move
+    /// is played on. With `chess960: true` castling is written as the king taking its rook.
+    fn to_uci(&self, args: &[Value]) -> Result<String, magnus::Error> {
+        let args = scan_args::<(), (Option<&Board>,), (), (), RHash, ()>(args)?;
+        let (board,) = args.optional;
+        let kwargs = get_kwargs::<_, (), (Option<bool>,), ()>(args.keywords, &[], &["chess960"])?;
+        let (chess960,) = kwargs.optional;
+        let board = board.map(|&board| chess_engine::Board::from(board));
+
+        let ruby = magnus::Ruby::get().unwrap();
+        let castle = |kingside: bool| {
+            let Some(board) = board else {
+                return Err(magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    "castling needs the board it's played on",
+                ));
+            };
+
+            let king = chess_engine::Position::king_pos(board.get_turn_color());
+            let col = match (kingside, chess960.unwrap_or(false)) {
+                (true, false) => 6,
+                (true, true) => 7,
+                (false, false) => 2,
+                (false, true) => 0,
+            };
+            Ok(format!(
+                "{king}{}",
+                chess_engine::Position::new(king.get_row(), col)
+            ))
+        };
+
+        match self.0 {
+            chess_engine::Move::Piece(from, to) => {
+                let promotes = board.is_some_and(|board| promotes(&board, from, to));
+                Ok(format!("{from}{to}{}", if promotes { "q" } else { "" }))
+            }
+            chess_engine::Move::KingSideCastle => castle(true),
+            chess_engine::Move::QueenSideCastle => castle(false),
+            chess_engine::Move::Resign => Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                "resigning has no UCI notation",
+            )),
+        }
     }
 
     fn is_queenside_castle(&self) -> bool {
@@ -182,6 +370,64 @@ impl Move {
         matches!(self.0, chess_engine::Move::Resign)
     }
 
+    /// Dump as the 16 bit encoding from `codec`, big-endian.
+    fn dump(&self, _level: i32) -> Result<RString, magnus::Error> {
+        let raw = codec::encode_move(self.0).ok_or_else(|| codec::off_board_move(self.0))?;
+
+        Ok(codec::binary_string(&raw.to_be_bytes()))
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        match codec::string_bytes(dump)[..] {
+            [high, low] => codec::decode_move(u16::from_be_bytes([high, low]))
+                .map(Self)
+                .ok_or_else(|| codec::invalid_dump("Move")),
+            _ => Err(codec::invalid_dump("Move")),
+        }
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn to_h(&self) -> Result<RHash, magnus::Error> {
+        let hash = magnus::Ruby::get().unwrap().hash_new();
+        let kind = match self.0 {
+            chess_engine::Move::Piece(from, to) => {
+                hash.aset(Symbol::new("from"), from.to_string())?;
+                hash.aset(Symbol::new("to"), to.to_string())?;
+                "piece"
+            }
+            chess_engine::Move::KingSideCastle => "kingside_castle",
+            chess_engine::Move::QueenSideCastle => "queenside_castle",
+            chess_engine::Move::Resign => "resign",
+        };
+        hash.aset(Symbol::new("kind"), kind)?;
+
+        Ok(hash)
+    }
+
+    fn from_h(hash: RHash) -> Result<Self, magnus::Error> {
+        let kind: String = codec::field(hash, "kind")?;
+
+        Ok(Self(match kind.as_str() {
+            "piece" => chess_engine::Move::Piece(
+                codec::parse_position(&codec::field::<String>(hash, "from")?)?,
+                codec::parse_position(&codec::field::<String>(hash, "to")?)?,
+            ),
+            "kingside_castle" => chess_engine::Move::KingSideCastle,
+            "queenside_castle" => chess_engine::Move::QueenSideCastle,
+            "resign" => chess_engine::Move::Resign,
+            _ => {
+                let ruby = magnus::Ruby::get().unwrap();
+                return Err(magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("unknown move kind {kind:?}"),
+                ));
+            }
+        }))
+    }
+
     fn piece_positions(&self) -> Option<(Position, Position)> {
         if let chess_engine::Move::Piece(pos1, pos2) = self.0 {
             Some((pos1.into(), pos2.into()))
@@ -199,50 +445,234 @@ impl Move {
     }
 }
 
-#[magnus::wrap(class = "Silverpoint::GameResult", size, free_immediately)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(transparent)]
-pub struct GameResult(chess_engine::GameResult);
+pub enum Reason {
+    Checkmate,
+    Resignation,
+    Stalemate,
+    Repetition,
+    FiftyMove,
+    InsufficientMaterial,
+    Timeout,
+    Agreement,
+    VariantWin,
+}
+
+impl Reason {
+    const ALL: [Self; 9] = [
+        Self::Checkmate,
+        Self::Resignation,
+        Self::Stalemate,
+        Self::Repetition,
+        Self::FiftyMove,
+        Self::InsufficientMaterial,
+        Self::Timeout,
+        Self::Agreement,
+        Self::VariantWin,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Checkmate => "checkmate",
+            Self::Resignation => "resignation",
+            Self::Stalemate => "stalemate",
+            Self::Repetition => "repetition",
+            Self::FiftyMove => "fifty_move",
+            Self::InsufficientMaterial => "insufficient_material",
+            Self::Timeout => "timeout",
+            Self::Agreement => "agreement",
+            Self::VariantWin => "variant_win",
+        }
+    }
+
+    fn from_symbol(reason: Symbol) -> Result<Self, magnus::Error> {
+        let name = reason.name()?;
+
+        Self::ALL
+            .into_iter()
+            .find(|reason| reason.name() == name)
+            .ok_or_else(|| {
+                let ruby = magnus::Ruby::get().unwrap();
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("unknown game result reason :{name}"),
+                )
+            })
+    }
+
+    /// Whether a game ending this way can be won, rather than only drawn.
+    fn is_decisive(self) -> bool {
+        matches!(
+            self,
+            Self::Checkmate | Self::Resignation | Self::Timeout | Self::VariantWin
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameResult {
+    result: chess_engine::GameResult,
+    /// Why the game ended, or `None` while it goes on or after an illegal move.
+    reason: Option<Reason>,
+    /// The board after the move, or as it stood if the move was illegal or the game ended
+    /// without one.
+    board: Option<chess_engine::Board>,
+    last_move: Option<chess_engine::Move>,
+}
+
+frozen_wrap!(GameResult, "Silverpoint::GameResult");
+
+/// Flags for the parts of a dumped `GameResult` that may be missing.
+const FINAL_BOARD: u8 = 1;
+const LAST_MOVE: u8 = 1 << 1;
+
+fn take_move(bytes: &mut &[u8]) -> Option<chess_engine::Move> {
+    let (&raw, rest) = bytes.split_first_chunk()?;
+    *bytes = rest;
+    codec::decode_move(u16::from_be_bytes(raw))
+}
+
+fn take_board(bytes: &mut &[u8]) -> Option<chess_engine::Board> {
+    let (board, rest) = bytes.split_first_chunk::<{ codec::BOARD_LEN }>()?;
+    *bytes = rest;
+    codec::decode_board(board)
+}
 
 impl From<chess_engine::GameResult> for GameResult {
+    /// Wins are taken to be by checkmate, and draws by stalemate, as `chess_engine` plays them.
     fn from(value: chess_engine::GameResult) -> Self {
-        Self(value)
+        let reason = match value {
+            chess_engine::GameResult::Victory(_) => Some(Reason::Checkmate),
+            chess_engine::GameResult::Stalemate => Some(Reason::Stalemate),
+            _ => None,
+        };
+
+        let board = match value {
+            chess_engine::GameResult::Continuing(board) => Some(board),
+            _ => None,
+        };
+
+        Self {
+            result: value,
+            reason,
+            board,
+            last_move: None,
+        }
     }
 }
 
 impl From<GameResult> for chess_engine::GameResult {
     fn from(value: GameResult) -> Self {
-        value.0
+        value.result
     }
 }
 
 impl GameResult {
+    pub fn victory(color: chess_engine::Color, reason: Reason) -> Self {
+        Self {
+            result: chess_engine::GameResult::Victory(color),
+            reason: Some(reason),
+            board: None,
+            last_move: None,
+        }
+    }
+
+    /// `chess_engine` has no draw besides stalemate, so every draw is a `Stalemate` underneath.
+    pub fn draw(reason: Reason) -> Self {
+        Self {
+            result: chess_engine::GameResult::Stalemate,
+            reason: Some(reason),
+            board: None,
+            last_move: None,
+        }
+    }
+
+    /// The same result, reached on `board` by playing `last_move`.
+    pub fn on(self, board: chess_engine::Board, last_move: Option<chess_engine::Move>) -> Self {
+        Self {
+            board: Some(board),
+            last_move,
+            ..self
+        }
+    }
+
+    fn new_victory(args: &[Value]) -> Result<Self, magnus::Error> {
+        let args = scan_args::<(&Color,), (Option<Symbol>,), (), (), (), ()>(args)?;
+        let (&color,) = args.required;
+        let (reason,) = args.optional;
+
+        let reason = reason.map_or(Ok(Reason::Checkmate), Reason::from_symbol)?;
+        if !reason.is_decisive() {
+            let ruby = magnus::Ruby::get().unwrap();
+            return Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                format!("a game can't be won by {}", reason.name()),
+            ));
+        }
+
+        Ok(Self::victory(color.into(), reason))
+    }
+
+    fn new_draw(reason: Symbol) -> Result<Self, magnus::Error> {
+        let reason = Reason::from_symbol(reason)?;
+        if matches!(reason, Reason::Checkmate | Reason::Resignation) {
+            let ruby = magnus::Ruby::get().unwrap();
+            return Err(magnus::Error::new(
+                ruby.exception_arg_error(),
+                format!("a game can't be drawn by {}", reason.name()),
+            ));
+        }
+
+        Ok(Self::draw(reason))
+    }
+
     fn is_continuing(&self) -> bool {
-        matches!(self.0, chess_engine::GameResult::Continuing(..))
+        matches!(self.result, chess_engine::GameResult::Continuing(..))
     }
 
     fn is_victory(&self) -> bool {
-        matches!(self.0, chess_engine::GameResult::Victory(..))
+        matches!(self.result, chess_engine::GameResult::Victory(..))
     }
 
+    /// True for every draw, as `chess_engine` reports them all as stalemate. Use `draw?` to
+    /// ask for any draw and `reason` to tell a real stalemate apart.
     fn is_stalemate(&self) -> bool {
-        matches!(self.0, chess_engine::GameResult::Stalemate)
+        matches!(self.result, chess_engine::GameResult::Stalemate)
+    }
+
+    fn is_draw(&self) -> bool {
+        matches!(self.result, chess_engine::GameResult::Stalemate)
+    }
+
+    fn is_decisive(&self) -> bool {
+        self.is_victory()
     }
 
     fn is_illegal_move(&self) -> bool {
-        matches!(self.0, chess_engine::GameResult::IllegalMove(..))
+        matches!(self.result, chess_engine::GameResult::IllegalMove(..))
     }
 
+    /// The board to play on from, so `nil` once the game is over.
     fn next_board(&self) -> Option<Board> {
-        if let chess_engine::GameResult::Continuing(board) = self.0 {
+        if let chess_engine::GameResult::Continuing(board) = self.result {
             Some(board.into())
         } else {
             None
         }
     }
 
+    /// The board the result was reached on, which unlike `next_board` is kept after mate,
+    /// stalemate or resignation. It's `nil` for results made without a board, like `new_draw`.
+    fn final_board(&self) -> Option<Board> {
+        self.board.map(Into::into)
+    }
+
+    fn last_move(&self) -> Option<Move> {
+        self.last_move.map(Into::into)
+    }
+
     fn winning_color(&self) -> Option<Color> {
-        if let chess_engine::GameResult::Victory(color) = self.0 {
+        if let chess_engine::GameResult::Victory(color) = self.result {
             Some(color.into())
         } else {
             None
@@ -250,15 +680,116 @@ impl GameResult {
     }
 
     fn illegal_move(&self) -> Option<Move> {
-        if let chess_engine::GameResult::IllegalMove(move_) = self.0 {
+        if let chess_engine::GameResult::IllegalMove(move_) = self.result {
             Some(move_.into())
         } else {
             None
         }
     }
 
+    fn reason(&self) -> Option<Symbol> {
+        self.reason.map(|reason| Symbol::new(reason.name()))
+    }
+
+    /// Dump as a byte for the kind of result, one for the reason and one of flags for what
+    /// follows: the illegal move, the final board and the last move.
+    fn dump(&self, _level: i32) -> Result<RString, magnus::Error> {
+        let encode_move = |m| codec::encode_move(m).ok_or_else(|| codec::off_board_move(m));
+
+        let reason = self.reason.map_or(0, |reason| reason as u8 + 1);
+        let flags = if self.board.is_some() { FINAL_BOARD } else { 0 }
+            | if self.last_move.is_some() {
+                LAST_MOVE
+            } else {
+                0
+            };
+        let kind = match self.result {
+            chess_engine::GameResult::Continuing(_) => 0,
+            chess_engine::GameResult::Victory(chess_engine::Color::White) => 1,
+            chess_engine::GameResult::Victory(chess_engine::Color::Black) => 2,
+            chess_engine::GameResult::Stalemate => 3,
+            chess_engine::GameResult::IllegalMove(_) => 4,
+        };
+
+        let mut bytes = vec![kind, reason, flags];
+        if let chess_engine::GameResult::IllegalMove(m) = self.result {
+            bytes.extend(encode_move(m)?.to_be_bytes());
+        }
+        if let Some(board) = self.board {
+            bytes.extend(codec::encode_board(&board));
+        }
+        if let Some(m) = self.last_move {
+            bytes.extend(encode_move(m)?.to_be_bytes());
+        }
+
+        Ok(codec::binary_string(&bytes))
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        let invalid = || codec::invalid_dump("GameResult");
+        let bytes = codec::string_bytes(dump);
+        let Some((&[kind, reason, flags], mut rest)) = bytes.split_first_chunk() else {
+            return Err(invalid());
+        };
+
+        let reason = match reason {
+            0 => None,
+            reason => Some(
+                *Reason::ALL
+                    .get(usize::from(reason) - 1)
+                    .ok_or_else(invalid)?,
+            ),
+        };
+        let illegal_move = (kind == 4)
+            .then(|| take_move(&mut rest).ok_or_else(invalid))
+            .transpose()?;
+        let board = (flags & FINAL_BOARD != 0)
+            .then(|| take_board(&mut rest).ok_or_else(invalid))
+            .transpose()?;
+        let last_move = (flags & LAST_MOVE != 0)
+            .then(|| take_move(&mut rest).ok_or_else(invalid))
+            .transpose()?;
+        if !rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let result = match (kind, board, illegal_move) {
+            (0, Some(board), _) => chess_engine::GameResult::Continuing(board),
+            (1, ..) => chess_engine::GameResult::Victory(chess_engine::Color::White),
+            (2, ..) => chess_engine::GameResult::Victory(chess_engine::Color::Black),
+            (3, ..) => chess_engine::GameResult::Stalemate,
+            (4, _, Some(m)) => chess_engine::GameResult::IllegalMove(m),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            result,
+            reason,
+            board,
+            last_move,
+        })
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    /// The result as a PGN `Result` tag, `*` for a game in progress.
+    fn score(&self) -> Option<&'static str> {
+        match self.result {
+            chess_engine::GameResult::Continuing(_) => Some("*"),
+            chess_engine::GameResult::Victory(chess_engine::Color::White) => Some("1-0"),
+            chess_engine::GameResult::Victory(chess_engine::Color::Black) => Some("0-1"),
+            chess_engine::GameResult::Stalemate => Some("1/2-1/2"),
+            chess_engine::GameResult::IllegalMove(_) => None,
+        }
+    }
+
     fn to_string(&self) -> String {
-        format!("{:?}", self.0)
+        match self.reason {
+            Some(reason) => format!("{:?} by {}", self.result, reason.name()),
+            None => format!("{:?}", self.result),
+        }
     }
 }
 
@@ -267,6 +798,11 @@ pub fn bind(ruby: &magnus::Ruby, module: impl magnus::Module) -> Result<(), magn
     class.const_set("White", Color(chess_engine::Color::White))?;
     class.const_set("Black", Color(chess_engine::Color::Black))?;
 
+    class.define_singleton_method("_load", function!(Color::load, 1))?;
+    class.define_method("_dump", method!(Color::dump, 1))?;
+    class.define_method("dup", method!(Color::dup, 0))?;
+    class.define_method("clone", method!(Color::dup, 0))?;
+
     class.define_method("inspect", method!(Color::inspect, 0))?;
     class.define_method("to_s", method!(Color::to_string, 0))?;
     class.define_method("==", method!(Color::eq, 1))?;
@@ -289,6 +825,13 @@ pub fn bind(ruby: &magnus::Ruby, module: impl magnus::Module) -> Result<(), magn
     class.define_method("kingside_rook?", method!(Piece::is_kingside_rook, 0))?;
     class.define_method("move", method!(Piece::move_to, 1))?;
 
+    class.define_singleton_method("_load", function!(Piece::load, 1))?;
+    class.define_singleton_method("from_h", function!(Piece::from_h, 1))?;
+    class.define_method("_dump", method!(Piece::dump, 1))?;
+    class.define_method("dup", method!(Piece::dup, 0))?;
+    class.define_method("clone", method!(Piece::dup, 0))?;
+    class.define_method("to_h", method!(Piece::to_h, 0))?;
+
     class.define_method("inspect", method!(Piece::inspect, 0))?;
     class.define_method("to_s", method!(Piece::to_string, 0))?;
     class.define_method("==", method!(Piece::eq, 1))?;
@@ -306,6 +849,7 @@ pub fn bind(ruby: &magnus::Ruby, module: impl magnus::Module) -> Result<(), magn
     class.define_singleton_method("new_resign", function!(Move::new_resign, 0))?;
     class.define_singleton_method("new_piece", function!(Move::new_piece, 2))?;
     class.define_singleton_method("parse", function!(Move::parse, 1))?;
+    class.define_singleton_method("from_uci", function!(Move::from_uci, 2))?;
 
     class.define_method("queenside_castle?", method!(Move::is_queenside_castle, 0))?;
     class.define_method("kingside_castle?", method!(Move::is_kingside_castle, 0))?;
@@ -313,19 +857,41 @@ pub fn bind(ruby: &magnus::Ruby, module: impl magnus::Module) -> Result<(), magn
     class.define_method("resign?", method!(Move::is_resign, 0))?;
     class.define_method("piece_positions", method!(Move::piece_positions, 0))?;
 
+    class.define_singleton_method("_load", function!(Move::load, 1))?;
+    class.define_singleton_method("from_h", function!(Move::from_h, 1))?;
+    class.define_method("_dump", method!(Move::dump, 1))?;
+    class.define_method("dup", method!(Move::dup, 0))?;
+    class.define_method("clone", method!(Move::dup, 0))?;
+    class.define_method("to_h", method!(Move::to_h, 0))?;
+    class.define_method("to_uci", method!(Move::to_uci, -1))?;
+
     class.define_method("inspect", method!(Move::inspect, 0))?;
     class.define_method("to_s", method!(Move::to_string, 0))?;
     class.define_method("==", method!(Move::eq, 1))?;
     class.define_method("!=", method!(Move::ne, 1))?;
 
     let class = module.define_class("GameResult", ruby.class_object())?;
+    class.define_singleton_method("new_victory", function!(GameResult::new_victory, -1))?;
+    class.define_singleton_method("new_draw", function!(GameResult::new_draw, 1))?;
+
     class.define_method("continuing?", method!(GameResult::is_continuing, 0))?;
     class.define_method("victory?", method!(GameResult::is_victory, 0))?;
     class.define_method("stalemate?", method!(GameResult::is_stalemate, 0))?;
+    class.define_method("draw?", method!(GameResult::is_draw, 0))?;
+    class.define_method("decisive?", method!(GameResult::is_decisive, 0))?;
     class.define_method("illegal_move?", method!(GameResult::is_illegal_move, 0))?;
     class.define_method("next_board", method!(GameResult::next_board, 0))?;
+    class.define_method("final_board", method!(GameResult::final_board, 0))?;
+    class.define_method("last_move", method!(GameResult::last_move, 0))?;
     class.define_method("winning_color", method!(GameResult::winning_color, 0))?;
     class.define_method("illegal_move", method!(GameResult::illegal_move, 0))?;
+    class.define_method("reason", method!(GameResult::reason, 0))?;
+    class.define_method("score", method!(GameResult::score, 0))?;
+
+    class.define_singleton_method("_load", function!(GameResult::load, 1))?;
+    class.define_method("_dump", method!(GameResult::dump, 1))?;
+    class.define_method("dup", method!(GameResult::dup, 0))?;
+    class.define_method("clone", method!(GameResult::dup, 0))?;
 
     class.define_method("inspect", method!(GameResult::to_string, 0))?;
     class.define_method("to_s", method!(GameResult::to_string, 0))?;
@@ -0,0 +1,174 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use magnus::{
+    value::{Lazy, ReprValue},
+    Attr, Class, ExceptionClass, IntoValue, Module, RModule,
+};
+
+use crate::{board::Board, enums::Move};
+
+fn class(ruby: &magnus::Ruby, name: &str) -> ExceptionClass {
+    ruby.define_module("Silverpoint")
+        .and_then(|module: RModule| module.const_get(name))
+        .expect("silverpoint errors are defined on init")
+}
+
+static PARSE_ERROR: Lazy<ExceptionClass> = Lazy::new(|ruby| class(ruby, "ParseError"));
+static ILLEGAL_MOVE_ERROR: Lazy<ExceptionClass> = Lazy::new(|ruby| class(ruby, "IllegalMoveError"));
+static AMBIGUOUS_MOVE_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| class(ruby, "AmbiguousMoveError"));
+static INVALID_POSITION_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| class(ruby, "InvalidPositionError"));
+
+fn new_error(
+    class: &Lazy<ExceptionClass>,
+    message: String,
+    fields: &[(&str, magnus::Value)],
+) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+
+    let build = || -> Result<magnus::Error, magnus::Error> {
+        let exception = ruby.get_inner(class).new_instance((message,))?;
+        for &(name, value) in fields {
+            let _: magnus::Value =
+                exception.funcall("instance_variable_set", (format!("@{name}"), value))?;
+        }
+        Ok(exception.into())
+    };
+
+    build().unwrap_or_else(|err| err)
+}
+
+pub struct ParseError {
+    pub input: String,
+    /// The 0-based character offset of the problem in `input`.
+    pub column: usize,
+    /// What was expected at `column`, like `"file a-h"`.
+    pub expected: &'static str,
+}
+
+impl ParseError {
+    pub fn new(input: &str, column: usize, expected: &'static str) -> Self {
+        Self {
+            input: input.to_owned(),
+            column,
+            expected,
+        }
+    }
+}
+
+impl From<ParseError> for magnus::Error {
+    fn from(value: ParseError) -> Self {
+        let ruby = magnus::Ruby::get().unwrap();
+        let found = value
+            .input
+            .chars()
+            .nth(value.column)
+            .map_or_else(|| "end of input".to_owned(), |c| format!("{c:?}"));
+
+        new_error(
+            &PARSE_ERROR,
+            format!(
+                "expected {} at column {} of {:?}, found {found}",
+                value.expected, value.column, value.input
+            ),
+            &[
+                ("input", value.input.into_value_with(&ruby)),
+                ("column", value.column.into_value_with(&ruby)),
+                ("expected", value.expected.into_value_with(&ruby)),
+            ],
+        )
+    }
+}
+
+pub fn illegal_move(
+    message: String,
+    m: chess_engine::Move,
+    board: chess_engine::Board,
+) -> magnus::Error {
+    suggest_moves(message, Some(m), board, &[])
+}
+
+/// Raise a `Silverpoint::IllegalMoveError` for input that isn't a legal move on `board`,
+/// suggesting legal moves it may have meant. `m` is the move, if the input names one.
+pub fn suggest_moves(
+    message: String,
+    m: Option<chess_engine::Move>,
+    board: chess_engine::Board,
+    suggestions: &[chess_engine::Move],
+) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    let suggestions = suggestions.iter().map(|&m| Move::from(m));
+
+    new_error(
+        &ILLEGAL_MOVE_ERROR,
+        message,
+        &[
+            ("move", m.map(Move::from).into_value_with(&ruby)),
+            ("board", Board::from(board).into_value_with(&ruby)),
+            ("suggestions", ruby.ary_from_iter(suggestions).as_value()),
+        ],
+    )
+}
+
+pub fn ambiguous_move(
+    message: String,
+    input: &str,
+    candidates: &[chess_engine::Move],
+) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    let candidates = candidates.iter().map(|&m| Move::from(m));
+
+    new_error(
+        &AMBIGUOUS_MOVE_ERROR,
+        message,
+        &[
+            ("input", input.into_value_with(&ruby)),
+            ("candidates", ruby.ary_from_iter(candidates).as_value()),
+        ],
+    )
+}
+
+pub fn invalid_position(input: &str, row: i32, col: i32) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+
+    new_error(
+        &INVALID_POSITION_ERROR,
+        format!("{input:?} is off the board"),
+        &[
+            ("input", input.into_value_with(&ruby)),
+            ("row", row.into_value_with(&ruby)),
+            ("col", col.into_value_with(&ruby)),
+        ],
+    )
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let error = module.define_error("Error", ruby.exception_standard_error())?;
+
+    let parse_error = module.define_error("ParseError", error)?;
+    for attr in ["input", "column", "expected"] {
+        parse_error.define_attr(attr, Attr::Read)?;
+    }
+    module.define_error("FenError", parse_error)?;
+
+    let illegal_move_error = module.define_error("IllegalMoveError", error)?;
+    for attr in ["move", "board", "suggestions"] {
+        illegal_move_error.define_attr(attr, Attr::Read)?;
+    }
+
+    let ambiguous_move_error = module.define_error("AmbiguousMoveError", error)?;
+    for attr in ["input", "candidates"] {
+        ambiguous_move_error.define_attr(attr, Attr::Read)?;
+    }
+
+    let invalid_position_error = module.define_error("InvalidPositionError", error)?;
+    for attr in ["input", "row", "col"] {
+        invalid_position_error.define_attr(attr, Attr::Read)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::fmt::Write;
+
+use chess_engine::{Color, Piece, Position};
+
+use crate::board::has_castling_right;
+
+fn piece_char(piece: Piece) -> char {
+    let c = match piece {
+        Piece::Pawn(..) => 'p',
+        Piece::Knight(..) => 'n',
+        Piece::Bishop(..) => 'b',
+        Piece::Rook(..) => 'r',
+        Piece::Queen(..) => 'q',
+        Piece::King(..) => 'k',
+    };
+
+    match piece.get_color() {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c,
+    }
+}
+
+/// `chess_engine` doesn't track the move clocks, so they are always written as `0 1`.
+pub fn to_fen(board: &chess_engine::Board) -> String {
+    let mut fen = String::new();
+
+    for row in (0..8).rev() {
+        let mut empty = 0;
+        for col in 0..8 {
+            match board.get_piece(Position::new(row, col)) {
+                Some(piece) => {
+                    if empty > 0 {
+                        write!(fen, "{empty}").unwrap();
+                        empty = 0;
+                    }
+                    fen.push(piece_char(piece));
+                }
+                None => empty += 1,
+            }
+        }
+        if empty > 0 {
+            write!(fen, "{empty}").unwrap();
+        }
+        if row > 0 {
+            fen.push('/');
+        }
+    }
+
+    fen.push(' ');
+    fen.push(match board.get_turn_color() {
+        Color::White => 'w',
+        Color::Black => 'b',
+    });
+
+    fen.push(' ');
+    let castling = [
+        (Color::White, true, 'K'),
+        (Color::White, false, 'Q'),
+        (Color::Black, true, 'k'),
+        (Color::Black, false, 'q'),
+    ]
+    .into_iter()
+    .filter(|&(color, kingside, _)| has_castling_right(board, color, kingside))
+    .map(|(_, _, c)| c)
+    .collect::<String>();
+    fen.push_str(if castling.is_empty() { "-" } else { &castling });
+
+    match board.get_en_passant() {
+        Some(pos) => write!(fen, " {pos}").unwrap(),
+        None => fen.push_str(" -"),
+    }
+
+    fen.push_str(" 0 1");
+    fen
+}
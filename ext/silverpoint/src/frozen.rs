@@ -0,0 +1,45 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+/// Wrap a `Copy` type as a Ruby class whose objects are frozen, and so shareable between
+/// Ractors.
+///
+/// `#[magnus::wrap]` has no way to freeze the objects it creates, so the wrapped struct is
+/// private and the type converts to and from Ruby through it instead. Types that aren't
+/// `Copy` use `frozen_wrap!(ref Type, ...)` and can only be borrowed from Ruby.
+macro_rules! frozen_wrap {
+    ($ty:ident, $class:literal) => {
+        $crate::frozen::frozen_wrap!(ref $ty, $class);
+
+        impl magnus::TryConvert for $ty {
+            fn try_convert(value: magnus::Value) -> Result<Self, magnus::Error> {
+                <&$ty>::try_convert(value).copied()
+            }
+        }
+    };
+    (ref $ty:ident, $class:literal) => {
+        const _: () = {
+            #[magnus::wrap(class = $class, size, free_immediately, frozen_shareable)]
+            struct Data($ty);
+
+            impl magnus::IntoValue for $ty {
+                fn into_value_with(self, ruby: &magnus::Ruby) -> magnus::Value {
+                    use magnus::value::ReprValue;
+
+                    let obj = ruby.obj_wrap(Data(self));
+                    obj.freeze();
+                    obj.as_value()
+                }
+            }
+
+            impl<'a> magnus::TryConvert for &'a $ty {
+                fn try_convert(value: magnus::Value) -> Result<Self, magnus::Error> {
+                    <&'a Data>::try_convert(value).map(|data| &data.0)
+                }
+            }
+        };
+    };
+}
+pub(crate) use frozen_wrap;
@@ -0,0 +1,334 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Square geometry on 0..64 indices, counting from a1 to h8 rank by rank.
+
+/// One of the eight directions a queen can move in, as seen from White's side.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::North => "north",
+            Self::NorthEast => "north_east",
+            Self::East => "east",
+            Self::SouthEast => "south_east",
+            Self::South => "south",
+            Self::SouthWest => "south_west",
+            Self::West => "west",
+            Self::NorthWest => "north_west",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|direction| direction.name() == name)
+    }
+
+    /// How far one step goes, in ranks and then files.
+    const fn step(self) -> (isize, isize) {
+        match self {
+            Self::North => (1, 0),
+            Self::NorthEast => (1, 1),
+            Self::East => (0, 1),
+            Self::SouthEast => (-1, 1),
+            Self::South => (-1, 0),
+            Self::SouthWest => (-1, -1),
+            Self::West => (0, -1),
+            Self::NorthWest => (1, -1),
+        }
+    }
+
+    /// Whether squares get higher indices going this way.
+    const fn is_ascending(self) -> bool {
+        let (rank, file) = self.step();
+        rank > 0 || (rank == 0 && file > 0)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        let (rank, file) = self.step();
+        rank != 0 && file != 0
+    }
+}
+
+const fn step(square: usize, direction: Direction) -> Option<usize> {
+    let (rank_step, file_step) = direction.step();
+
+    match (
+        (square / 8).checked_add_signed(rank_step),
+        (square % 8).checked_add_signed(file_step),
+    ) {
+        (Some(rank @ 0..=7), Some(file @ 0..=7)) => Some(rank * 8 + file),
+        _ => None,
+    }
+}
+
+static RAYS: [[u64; 64]; 8] = {
+    let mut rays = [[0; 64]; 8];
+
+    let mut d = 0;
+    while d < 8 {
+        let mut from = 0;
+        while from < 64 {
+            let mut square = from;
+            while let Some(next) = step(square, Direction::ALL[d]) {
+                rays[d][from] |= 1 << next;
+                square = next;
+            }
+            from += 1;
+        }
+        d += 1;
+    }
+
+    rays
+};
+
+static BETWEEN: [[u64; 64]; 64] = {
+    let mut between = [[0; 64]; 64];
+
+    let mut d = 0;
+    while d < 8 {
+        let mut from = 0;
+        while from < 64 {
+            let mut squares = 0;
+            let mut square = from;
+            while let Some(next) = step(square, Direction::ALL[d]) {
+                between[from][next] = squares;
+                squares |= 1 << next;
+                square = next;
+            }
+            from += 1;
+        }
+        d += 1;
+    }
+
+    between
+};
+
+const fn leaper_table<const N: usize>(jumps: [(isize, isize); N]) -> [u64; 64] {
+    let mut table = [0; 64];
+
+    let mut square: usize = 0;
+    while square < 64 {
+        let mut i = 0;
+        while i < N {
+            let (rank_jump, file_jump) = jumps[i];
+            if let (Some(rank @ 0..=7), Some(file @ 0..=7)) = (
+                (square / 8).checked_add_signed(rank_jump),
+                (square % 8).checked_add_signed(file_jump),
+            ) {
+                table[square] |= 1 << (rank * 8 + file);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+
+    table
+}
+
+static KNIGHT: [u64; 64] = leaper_table([
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+    (1, -2),
+    (2, -1),
+]);
+
+static KING: [u64; 64] = leaper_table([
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+]);
+
+/// Pawn captures, white's then black's.
+static PAWN: [[u64; 64]; 2] = [
+    leaper_table([(1, -1), (1, 1)]),
+    leaper_table([(-1, -1), (-1, 1)]),
+];
+
+// Distances on the board fit easily in a byte, and `u8::try_from` isn't `const`.
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow(distance: usize) -> u8 {
+    distance as u8
+}
+
+static DISTANCE: [[u8; 64]; 64] = {
+    let mut distance = [[0; 64]; 64];
+
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            let ranks = (a / 8).abs_diff(b / 8);
+            let files = (a % 8).abs_diff(b % 8);
+            distance[a][b] = narrow(if ranks > files { ranks } else { files });
+            b += 1;
+        }
+        a += 1;
+    }
+
+    distance
+};
+
+static MANHATTAN: [[u8; 64]; 64] = {
+    let mut manhattan = [[0; 64]; 64];
+
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            let ranks = (a / 8).abs_diff(b / 8);
+            let files = (a % 8).abs_diff(b % 8);
+            manhattan[a][b] = narrow(ranks + files);
+            b += 1;
+        }
+        a += 1;
+    }
+
+    manhattan
+};
+
+/// The squares of a bitboard, taken from one end so they come out in order along a line.
+#[derive(Clone)]
+pub struct Squares {
+    bits: u64,
+    ascending: bool,
+}
+
+impl Iterator for Squares {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let square = if self.ascending {
+            self.bits.trailing_zeros()
+        } else {
+            self.bits.ilog2()
+        };
+        self.bits &= !(1 << square);
+
+        usize::try_from(square).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.bits.count_ones()).unwrap_or(0);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Squares {}
+
+/// The squares from `square` to the edge of the board going `direction`, nearest first.
+pub fn ray(square: usize, direction: Direction) -> Squares {
+    Squares {
+        bits: RAYS[direction as usize][square],
+        ascending: direction.is_ascending(),
+    }
+}
+
+/// The squares strictly between `a` and `b`, going from `a`, or none if they don't share a
+/// rank, file or diagonal.
+pub fn between(a: usize, b: usize) -> Squares {
+    Squares {
+        bits: BETWEEN[a][b],
+        ascending: a < b,
+    }
+}
+
+pub fn direction(a: usize, b: usize) -> Option<Direction> {
+    Direction::ALL
+        .into_iter()
+        .find(|&direction| RAYS[direction as usize][a] & 1 << b != 0)
+}
+
+/// The squares a rook, bishop or queen on `square` attacks going `direction`, up to and
+/// including the first of the `occupied` squares in the way.
+pub fn slide(square: usize, direction: Direction, occupied: u64) -> u64 {
+    let ray = RAYS[direction as usize][square];
+    match ray & occupied {
+        0 => ray,
+        blockers => {
+            let blocker = Squares {
+                bits: blockers,
+                ascending: direction.is_ascending(),
+            }
+            .next()
+            .unwrap_or(square);
+            ray ^ RAYS[direction as usize][blocker]
+        }
+    }
+}
+
+pub fn knight_attacks(square: usize) -> u64 {
+    KNIGHT[square]
+}
+
+pub fn king_attacks(square: usize) -> u64 {
+    KING[square]
+}
+
+/// The squares a pawn on `square` attacks, moving up the board if it's white.
+pub fn pawn_attacks(square: usize, white: bool) -> u64 {
+    PAWN[usize::from(!white)][square]
+}
+
+/// How many king moves it takes to get from `a` to `b`, the Chebyshev distance.
+pub fn distance(a: usize, b: usize) -> u8 {
+    DISTANCE[a][b]
+}
+
+/// How many rook steps it takes to get from `a` to `b`, the Manhattan distance.
+pub fn manhattan(a: usize, b: usize) -> u8 {
+    MANHATTAN[a][b]
+}
+
+/// Whether `square` is a light square. a1 is dark.
+pub fn is_light(square: usize) -> bool {
+    (square / 8 + square % 8) % 2 == 1
+}
+
+/// `square` reflected across the middle of the board, swapping the a and h files.
+pub fn mirror(square: usize) -> usize {
+    square ^ 0o7
+}
+
+/// `square` reflected across the middle of the board, swapping the first and eighth ranks.
+pub fn flip(square: usize) -> usize {
+    square ^ 0o70
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::{
+    any::Any,
+    cell::Cell,
+    ffi::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[derive(Default)]
+struct Flag {
+    requested: AtomicBool,
+    /// Set once the running code has seen the request, so its result is incomplete.
+    seen: AtomicBool,
+}
+
+thread_local! {
+    static CURRENT: Cell<*const Flag> = const { Cell::new(ptr::null()) };
+}
+
+/// Whether Ruby has asked the code running without the GVL to stop.
+pub fn interrupted() -> bool {
+    CURRENT.with(|current| {
+        // SAFETY: the flag is only current while `run` is running the call that owns it.
+        let Some(flag) = (unsafe { current.get().as_ref() }) else {
+            return false;
+        };
+
+        let requested = flag.requested.load(Ordering::Relaxed);
+        if requested {
+            flag.seen.store(true, Ordering::Relaxed);
+        }
+        requested
+    })
+}
+
+struct Call<'a, F, R> {
+    func: &'a mut F,
+    flag: &'a Flag,
+    result: Option<std::thread::Result<R>>,
+}
+
+unsafe extern "C" fn run<F, R>(data: *mut c_void) -> *mut c_void
+where
+    F: FnMut() -> R,
+{
+    let call = &mut *data.cast::<Call<'_, F, R>>();
+
+    let outer = CURRENT.replace(call.flag);
+    // A panic can't unwind into Ruby, so it's caught here and raised once the GVL is back.
+    call.result = Some(panic::catch_unwind(AssertUnwindSafe(&mut *call.func)));
+    CURRENT.set(outer);
+
+    ptr::null_mut()
+}
+
+unsafe extern "C" fn request_stop(data: *mut c_void) {
+    (*data.cast::<Flag>())
+        .requested
+        .store(true, Ordering::Relaxed);
+}
+
+fn panic_error(panic: &(dyn Any + Send)) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|&message| message.to_owned())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".to_owned());
+
+    magnus::Error::new(ruby.exception_fatal(), message)
+}
+
+pub fn without_gvl<F, R>(mut func: F) -> Result<R, magnus::Error>
+where
+    F: FnMut() -> R,
+{
+    let ruby = magnus::Ruby::get().unwrap();
+
+    loop {
+        let flag = Flag::default();
+        let mut call = Call {
+            func: &mut func,
+            flag: &flag,
+            result: None,
+        };
+
+        // SAFETY: `call` and `flag` outlive the call. Unlike `rb_thread_call_without_gvl`,
+        // this never raises, which would skip our destructors; if an interrupt is already
+        // pending it returns without running `run` at all.
+        unsafe {
+            rb_sys::rb_thread_call_without_gvl2(
+                Some(run::<F, R>),
+                ptr::from_mut(&mut call).cast(),
+                Some(request_stop),
+                ptr::from_ref(&flag).cast_mut().cast(),
+            );
+        }
+
+        match call.result {
+            Some(Err(panic)) => return Err(panic_error(&*panic)),
+            Some(Ok(value)) if !flag.seen.load(Ordering::Relaxed) => {
+                ruby.thread_check_ints()?;
+                return Ok(value);
+            }
+            // `func` gave up partway, or never ran because an interrupt was already pending.
+            // Its result is thrown away and the interrupt runs; if that doesn't raise, `func`
+            // starts over.
+            Some(Ok(_)) | None => ruby.thread_check_ints()?,
+        }
+    }
+}
+
+/// Evaluate an expression without the GVL, as a `Result` that fails if an interrupt raises.
+///
+/// An interrupted expression stops at its next [`interrupted`] check, so what it returns is
+/// partial and is never handed back. When the interrupt doesn't raise, like a signal trap
+/// that returns, the expression is evaluated again from the start.
+macro_rules! no_gvl {
+    ($fun:expr) => {
+        $crate::gvl::without_gvl(|| $fun)
+    };
+}
+pub(crate) use no_gvl;
@@ -0,0 +1,402 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::{Color, Evaluate, Move, Piece, Position};
+use magnus::method;
+use magnus::Module;
+use magnus::Symbol;
+
+use crate::attacks;
+use crate::board::{has_castling_right, legal_moves};
+use crate::codec::color_name;
+use crate::enums;
+use crate::frozen::frozen_wrap;
+use crate::geometry;
+use crate::position::{from_square_index, square_index};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Reason {
+    NoPiece,
+    WrongColor,
+    InvalidMovement,
+    Blocked,
+    KingInCheck,
+    CastlingThroughCheck,
+    NoCastlingRights,
+    EnPassantUnavailable,
+}
+
+impl Reason {
+    fn name(self) -> &'static str {
+        match self {
+            Self::NoPiece => "no_piece",
+            Self::WrongColor => "wrong_color",
+            Self::InvalidMovement => "invalid_movement",
+            Self::Blocked => "blocked",
+            Self::KingInCheck => "king_in_check",
+            Self::CastlingThroughCheck => "castling_through_check",
+            Self::NoCastlingRights => "no_castling_rights",
+            Self::EnPassantUnavailable => "en_passant_unavailable",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Illegality {
+    reason: Reason,
+    m: Move,
+    color: Color,
+    /// The piece the move tries to move, the king for castles.
+    piece: Option<Piece>,
+    /// The piece to blame: the one in the way, giving check or pinning, or the pawn that
+    /// can't be taken en passant.
+    culprit: Option<Piece>,
+    /// The square at issue: the empty one moved from, the one in the way or the one castled
+    /// through.
+    square: Option<Position>,
+    /// Whether the piece is pinned to its king by the culprit.
+    pinned: bool,
+}
+
+frozen_wrap!(Illegality, "Silverpoint::Illegality");
+
+fn describe(piece: Piece) -> String {
+    format!("the {} on {}", piece.get_name(), piece.get_pos())
+}
+
+fn castle(board: &chess_engine::Board, kingside: bool, base: Illegality) -> Illegality {
+    let color = base.color;
+    let king = Position::king_pos(color);
+    if !has_castling_right(board, color, kingside) {
+        return Illegality {
+            reason: Reason::NoCastlingRights,
+            ..base
+        };
+    }
+
+    let base = Illegality {
+        piece: board.get_piece(king),
+        ..base
+    };
+    let rook = Position::new(king.get_row(), if kingside { 7 } else { 0 });
+    if let Some((square, culprit)) = geometry::between(square_index(king), square_index(rook))
+        .map(from_square_index)
+        .find_map(|square| Some((square, board.get_piece(square)?)))
+    {
+        return Illegality {
+            reason: Reason::Blocked,
+            culprit: Some(culprit),
+            square: Some(square),
+            ..base
+        };
+    }
+
+    // The king can't castle out of check, through an attacked square or into check.
+    let step = |square: Position| {
+        if kingside {
+            square.next_right()
+        } else {
+            square.next_left()
+        }
+    };
+    for square in [king, step(king), step(step(king))] {
+        if let Some(&attacker) = attacks::attackers(board, square_index(square), !color).first() {
+            return Illegality {
+                reason: Reason::CastlingThroughCheck,
+                culprit: Some(attacker),
+                square: Some(square),
+                ..base
+            };
+        }
+    }
+
+    Illegality {
+        reason: Reason::InvalidMovement,
+        ..base
+    }
+}
+
+/// Why a pawn can't move from `from` to `to` on `board`, or `None` if it moves that way.
+fn pawn_move(
+    board: &chess_engine::Board,
+    from: Position,
+    to: Position,
+    base: Illegality,
+) -> Option<Illegality> {
+    let color = base.color;
+    let invalid = Illegality {
+        reason: Reason::InvalidMovement,
+        ..base
+    };
+    let blocked = |square: Position| {
+        board.get_piece(square).map(|culprit| Illegality {
+            reason: Reason::Blocked,
+            culprit: Some(culprit),
+            square: Some(square),
+            ..base
+        })
+    };
+
+    let up = from.pawn_up(color);
+    if to == up || (to == up.pawn_up(color) && from.is_starting_pawn(color)) {
+        return blocked(up).or_else(|| blocked(to));
+    }
+    if geometry::pawn_attacks(square_index(from), color == Color::White) & 1 << square_index(to)
+        == 0
+    {
+        return Some(invalid);
+    }
+
+    // Taking onto an empty square is only allowed en passant.
+    if board.get_piece(to).is_some() || board.get_en_passant() == Some(to) {
+        return None;
+    }
+    let passed = to.pawn_back(color);
+    Some(match board.get_piece(passed) {
+        Some(culprit @ Piece::Pawn(..)) if culprit.get_color() != color => Illegality {
+            reason: Reason::EnPassantUnavailable,
+            culprit: Some(culprit),
+            square: Some(passed),
+            ..base
+        },
+        _ => invalid,
+    })
+}
+
+fn piece_move(
+    board: &chess_engine::Board,
+    from: Position,
+    to: Position,
+    base: Illegality,
+) -> Illegality {
+    let color = base.color;
+    let Some(piece) = board.get_piece(from) else {
+        return Illegality {
+            reason: Reason::NoPiece,
+            square: Some(from),
+            ..base
+        };
+    };
+
+    let base = Illegality {
+        piece: Some(piece),
+        ..base
+    };
+    let invalid = Illegality {
+        reason: Reason::InvalidMovement,
+        ..base
+    };
+    if piece.get_color() != color {
+        return Illegality {
+            reason: Reason::WrongColor,
+            ..base
+        };
+    }
+    if to.is_off_board() || to == from {
+        return invalid;
+    }
+
+    let blocked = |square: Position| {
+        board.get_piece(square).map(|culprit| Illegality {
+            reason: Reason::Blocked,
+            culprit: Some(culprit),
+            square: Some(square),
+            ..base
+        })
+    };
+    let occupant = board.get_piece(to);
+    if occupant.is_some_and(|occupant| occupant.get_color() == color) {
+        return blocked(to).unwrap_or(invalid);
+    }
+
+    let (from_square, to_square) = (square_index(from), square_index(to));
+    match piece {
+        Piece::Pawn(..) => {
+            if let Some(illegality) = pawn_move(board, from, to, base) {
+                return illegality;
+            }
+        }
+        Piece::Knight(..) => {
+            if geometry::knight_attacks(from_square) & 1 << to_square == 0 {
+                return invalid;
+            }
+        }
+        Piece::King(..) => {
+            if from == Position::king_pos(color)
+                && to.get_row() == from.get_row()
+                && (to.get_col() - from.get_col()).abs() == 2
+            {
+                return castle(board, to.get_col() > from.get_col(), base);
+            }
+            if geometry::king_attacks(from_square) & 1 << to_square == 0 {
+                return invalid;
+            }
+        }
+        _ => {
+            if !geometry::direction(from_square, to_square)
+                .is_some_and(|direction| attacks::slides_along(piece, direction))
+            {
+                return invalid;
+            }
+            if let Some(blocked) = geometry::between(from_square, to_square)
+                .find_map(|square| blocked(from_square_index(square)))
+            {
+                return blocked;
+            }
+        }
+    }
+
+    // The piece can move there, so the move must leave the king in check.
+    let next = board.apply_eval_move(Move::Piece(from, to));
+    match attacks::checkers(&next, color).first() {
+        Some(&checker) => Illegality {
+            reason: Reason::KingInCheck,
+            culprit: Some(checker),
+            pinned: attacks::pins(board, color)
+                .iter()
+                .any(|pin| pin.piece == piece && pin.slider == checker),
+            ..base
+        },
+        None => invalid,
+    }
+}
+
+pub(crate) fn explain(board: &chess_engine::Board, m: Move) -> Option<Illegality> {
+    if m == Move::Resign || legal_moves(board).contains(&m) {
+        return None;
+    }
+
+    let base = Illegality {
+        reason: Reason::InvalidMovement,
+        m,
+        color: board.get_turn_color(),
+        piece: None,
+        culprit: None,
+        square: None,
+        pinned: false,
+    };
+
+    Some(match m {
+        Move::KingSideCastle => castle(board, true, base),
+        Move::QueenSideCastle => castle(board, false, base),
+        Move::Piece(from, to) => piece_move(board, from, to, base),
+        Move::Resign => base,
+    })
+}
+
+impl Illegality {
+    /// Whether the move is only illegal because of where it leaves the king, so the piece
+    /// can otherwise make it.
+    pub(crate) fn is_pseudo_legal(&self) -> bool {
+        matches!(
+            self.reason,
+            Reason::KingInCheck | Reason::CastlingThroughCheck
+        )
+    }
+
+    /// Explain the problem, like "the knight on e2 is pinned to your king by the rook on e8".
+    pub(crate) fn message(&self) -> String {
+        let piece = self.piece.map(describe).unwrap_or_default();
+        let culprit = self.culprit.map(describe).unwrap_or_default();
+        let square = self
+            .square
+            .map(|square| square.to_string())
+            .unwrap_or_default();
+        let side = if self.m == Move::QueenSideCastle {
+            "queenside"
+        } else {
+            "kingside"
+        };
+
+        match self.reason {
+            Reason::NoPiece => format!("there's no piece on {square}"),
+            Reason::WrongColor => format!(
+                "it's {} to move, and {piece} is {}",
+                color_name(self.color),
+                color_name(!self.color)
+            ),
+            Reason::InvalidMovement => match (self.piece, self.m) {
+                (Some(piece), Move::Piece(from, to)) => {
+                    format!("a {} can't move from {from} to {to}", piece.get_name())
+                }
+                _ => format!("{} isn't a legal move", self.m),
+            },
+            Reason::Blocked => format!("{culprit} is in the way"),
+            Reason::KingInCheck if self.pinned => {
+                format!("{piece} is pinned to your king by {culprit}")
+            }
+            Reason::KingInCheck => format!("your king would be in check from {culprit}"),
+            Reason::CastlingThroughCheck
+                if self.square == self.piece.map(|king| king.get_pos()) =>
+            {
+                format!("you can't castle out of check from {culprit}")
+            }
+            Reason::CastlingThroughCheck => {
+                format!("you can't castle through {square}, which {culprit} attacks")
+            }
+            Reason::NoCastlingRights => {
+                format!("{} can no longer castle {side}", color_name(self.color))
+            }
+            Reason::EnPassantUnavailable => format!("{culprit} can't be taken en passant now"),
+        }
+    }
+
+    fn reason(&self) -> Symbol {
+        Symbol::new(self.reason.name())
+    }
+
+    fn get_move(&self) -> enums::Move {
+        self.m.into()
+    }
+
+    fn piece(&self) -> Option<enums::Piece> {
+        self.piece.map(Into::into)
+    }
+
+    fn culprit(&self) -> Option<enums::Piece> {
+        self.culprit.map(Into::into)
+    }
+
+    fn square(&self) -> Option<crate::position::Position> {
+        self.square.map(Into::into)
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::Illegality {}: {}>",
+            self.reason.name(),
+            self.message()
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("Illegality", ruby.class_object())?;
+    class.define_method("reason", method!(Illegality::reason, 0))?;
+    class.define_method("move", method!(Illegality::get_move, 0))?;
+    class.define_method("piece", method!(Illegality::piece, 0))?;
+    class.define_method("culprit", method!(Illegality::culprit, 0))?;
+    class.define_method("square", method!(Illegality::square, 0))?;
+    class.define_method("pinned?", method!(Illegality::is_pinned, 0))?;
+    class.define_method("message", method!(Illegality::message, 0))?;
+
+    class.define_method("dup", method!(Illegality::dup, 0))?;
+    class.define_method("clone", method!(Illegality::dup, 0))?;
+
+    class.define_method("inspect", method!(Illegality::inspect, 0))?;
+    class.define_method("to_s", method!(Illegality::message, 0))?;
+    class.define_method("==", method!(Illegality::eq, 1))?;
+    class.define_method("!=", method!(Illegality::ne, 1))?;
+
+    Ok(())
+}
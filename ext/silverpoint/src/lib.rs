@@ -4,18 +4,59 @@
 // https://opensource.org/licenses/MIT
 #![warn(rust_2018_idioms, clippy::all, clippy::pedantic)]
 
+mod attacks;
 mod board;
+mod book;
+mod clock;
+mod codec;
+mod engine;
 mod enums;
+mod errors;
+mod fen;
+mod frozen;
+mod geometry;
+mod gvl;
+mod illegality;
+mod mate;
+mod move_info;
+mod notation;
+mod pawns;
+mod polyglot;
 mod position;
+mod puzzles;
+mod render;
+mod review;
+mod search;
 mod square;
+mod svg;
+mod syzygy;
+mod tablebase;
+mod tactics;
 
 #[magnus::init]
 pub fn init(ruby: &magnus::Ruby) -> Result<(), magnus::Error> {
+    // Nothing here keeps global state, so methods can be called from any Ractor. This has
+    // to come before they are defined.
+    // SAFETY: only sets a flag read when methods are defined.
+    unsafe { rb_sys::rb_ext_ractor_safe(true) };
+
     let module = magnus::define_module("Silverpoint")?;
+    errors::bind(ruby, module)?;
     board::bind(ruby, module)?;
     square::bind(ruby, module)?;
     position::bind(ruby, module)?;
     enums::bind(ruby, module)?;
+    move_info::bind(ruby, module)?;
+    attacks::bind(ruby, module)?;
+    illegality::bind(ruby, module)?;
+    tactics::bind(ruby, module)?;
+    pawns::bind(ruby, module)?;
+    book::bind(ruby, module)?;
+    tablebase::bind(ruby, module)?;
+    puzzles::bind(ruby, module)?;
+    review::bind(ruby, module)?;
+    clock::bind(ruby, module)?;
+    engine::bind(ruby, module)?;
 
     Ok(())
 }
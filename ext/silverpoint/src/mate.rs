@@ -0,0 +1,155 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::collections::BTreeMap;
+
+use chess_engine::{Evaluate, Move};
+
+use crate::{
+    board::{is_mate, legal_moves},
+    gvl,
+};
+
+/// Which of the attacker's moves to search. The defender's are always all searched.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Width {
+    /// Only checks and captures. Leaving out attacker moves can miss a mate but never
+    /// finds a false one, so a mate found this way stands.
+    Forcing,
+    /// Every move, needed to show there's no mate.
+    Full,
+}
+
+struct Candidate {
+    m: Move,
+    next: chess_engine::Board,
+    check: bool,
+    capture: bool,
+}
+
+/// Legal moves with checks first, then captures, then quiet moves.
+fn ordered_moves(board: &chess_engine::Board) -> Vec<Candidate> {
+    let color = board.get_turn_color();
+    let mut moves = legal_moves(board)
+        .into_iter()
+        .map(|m| {
+            let next = board.apply_eval_move(m);
+            Candidate {
+                m,
+                next,
+                check: next.is_in_check(!color),
+                capture: matches!(m, Move::Piece(_, to) if board.has_enemy_piece(to, color)),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    moves.sort_by_key(|candidate| (!candidate.check, !candidate.capture));
+
+    moves
+}
+
+/// A search that remembers which positions it has already solved, since the same position
+/// is often reached by playing the same moves in a different order.
+#[derive(Default)]
+struct Solver {
+    solved: BTreeMap<(chess_engine::Board, usize, Width), Option<Vec<Move>>>,
+}
+
+impl Solver {
+    fn attack(
+        &mut self,
+        board: &chess_engine::Board,
+        moves: usize,
+        width: Width,
+    ) -> Option<Vec<Move>> {
+        if let Some(line) = self.solved.get(&(*board, moves, width)) {
+            return line.clone();
+        }
+
+        let mut mate = None;
+        for candidate in ordered_moves(board) {
+            // Giving up is as good as any answer, since an interrupted search is thrown away.
+            if gvl::interrupted() {
+                return None;
+            }
+            // A mate has to give check, so the last move can't be quiet, and the moves are
+            // sorted so nothing after the first one left out is searched either.
+            if (moves == 1 && !candidate.check)
+                || (width == Width::Forcing && !candidate.check && !candidate.capture)
+            {
+                break;
+            }
+
+            if candidate.check && is_mate(&candidate.next) {
+                mate = Some(vec![candidate.m]);
+                break;
+            }
+
+            if moves > 1 {
+                if let Some(mut line) = self.defend(&candidate.next, moves - 1, width) {
+                    line.insert(0, candidate.m);
+                    mate = Some(line);
+                    break;
+                }
+            }
+        }
+
+        self.solved.insert((*board, moves, width), mate.clone());
+        mate
+    }
+
+    fn defend(
+        &mut self,
+        board: &chess_engine::Board,
+        moves: usize,
+        width: Width,
+    ) -> Option<Vec<Move>> {
+        let mut longest: Option<Vec<Move>> = None;
+
+        for candidate in ordered_moves(board) {
+            let mut line = self.attack(&candidate.next, moves, width)?;
+            line.insert(0, candidate.m);
+
+            if longest
+                .as_ref()
+                .is_none_or(|longest| line.len() > longest.len())
+            {
+                longest = Some(line);
+            }
+        }
+
+        // No replies and no mate means stalemate, which isn't a win.
+        longest
+    }
+}
+
+/// The shortest forced mate in at most `max_moves` moves, trying checks and captures alone
+/// before every move at each length.
+pub fn find_mate(board: &chess_engine::Board, max_moves: usize) -> Option<Vec<Move>> {
+    let mut solver = Solver::default();
+    (1..=max_moves).find_map(|moves| {
+        solver
+            .attack(board, moves, Width::Forcing)
+            .or_else(|| solver.attack(board, moves, Width::Full))
+    })
+}
+
+/// Every move that forces mate in at most `max_moves` moves, including mates in one.
+pub fn mating_moves(board: &chess_engine::Board, max_moves: usize) -> Vec<Move> {
+    let mut solver = Solver::default();
+    let mut mates = |next: &chess_engine::Board, width| {
+        max_moves > 1 && solver.defend(next, max_moves - 1, width).is_some()
+    };
+
+    ordered_moves(board)
+        .into_iter()
+        .filter(|candidate| {
+            (candidate.check && is_mate(&candidate.next))
+                || mates(&candidate.next, Width::Forcing)
+                || mates(&candidate.next, Width::Full)
+        })
+        .map(|candidate| candidate.m)
+        .collect()
+}
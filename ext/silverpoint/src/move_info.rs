@@ -0,0 +1,179 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::Evaluate;
+use magnus::method;
+use magnus::Module;
+
+use crate::board::is_mate;
+use crate::enums::{Move, Piece};
+use crate::frozen::frozen_wrap;
+use crate::position::Position;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MoveInfo {
+    m: chess_engine::Move,
+    /// The piece that moves, the king for castles.
+    piece: chess_engine::Piece,
+    from: chess_engine::Position,
+    to: chess_engine::Position,
+    /// The piece taken and the square it was taken on, which is behind `to` en passant.
+    captured: Option<(chess_engine::Piece, chess_engine::Position)>,
+    en_passant: bool,
+    promotion: Option<chess_engine::Piece>,
+    /// Where the rook goes from and to when castling.
+    rook: Option<(chess_engine::Position, chess_engine::Position)>,
+    check: bool,
+    mate: bool,
+}
+
+frozen_wrap!(MoveInfo, "Silverpoint::MoveInfo");
+
+impl MoveInfo {
+    pub(crate) fn new(board: &chess_engine::Board, m: chess_engine::Move) -> Option<Self> {
+        let color = board.get_turn_color();
+        let king = chess_engine::Position::king_pos(color);
+        let (from, to, rook) = match m {
+            chess_engine::Move::Piece(from, to) => (from, to, None),
+            chess_engine::Move::KingSideCastle => (
+                king,
+                king.next_right().next_right(),
+                Some((
+                    chess_engine::Position::new(king.get_row(), 7),
+                    king.next_right(),
+                )),
+            ),
+            chess_engine::Move::QueenSideCastle => (
+                king,
+                king.next_left().next_left(),
+                Some((
+                    chess_engine::Position::new(king.get_row(), 0),
+                    king.next_left(),
+                )),
+            ),
+            chess_engine::Move::Resign => return None,
+        };
+        let piece = board.get_piece(from)?;
+
+        // Only en passant takes diagonally onto an empty square.
+        let en_passant =
+            piece.is_pawn() && from.get_col() != to.get_col() && board.has_no_piece(to);
+        let capture_square = if en_passant { to.pawn_back(color) } else { to };
+        let captured = board
+            .get_piece(capture_square)
+            .filter(|captured| rook.is_none() && captured.get_color() != color)
+            .map(|captured| (captured, capture_square));
+
+        let promotion = (piece.is_pawn() && (to.get_row() == 0 || to.get_row() == 7))
+            .then_some(chess_engine::Piece::Queen(color, to));
+
+        let next = board.apply_eval_move(m);
+        let check = next.is_in_check(next.get_turn_color());
+
+        Some(Self {
+            m,
+            piece,
+            from,
+            to,
+            captured,
+            en_passant,
+            promotion,
+            rook,
+            check,
+            mate: check && is_mate(&next),
+        })
+    }
+
+    fn get_move(&self) -> Move {
+        self.m.into()
+    }
+
+    fn get_piece(&self) -> Piece {
+        self.piece.into()
+    }
+
+    fn get_from(&self) -> Position {
+        self.from.into()
+    }
+
+    fn get_to(&self) -> Position {
+        self.to.into()
+    }
+
+    fn get_captured(&self) -> Option<Piece> {
+        self.captured.map(|(piece, _)| piece.into())
+    }
+
+    fn get_capture_square(&self) -> Option<Position> {
+        self.captured.map(|(_, square)| square.into())
+    }
+
+    fn is_capture(&self) -> bool {
+        self.captured.is_some()
+    }
+
+    fn is_check(&self) -> bool {
+        self.check
+    }
+
+    fn is_mate(&self) -> bool {
+        self.mate
+    }
+
+    fn is_castle(&self) -> bool {
+        self.rook.is_some()
+    }
+
+    fn is_en_passant(&self) -> bool {
+        self.en_passant
+    }
+
+    fn get_promotion(&self) -> Option<Piece> {
+        self.promotion.map(Into::into)
+    }
+
+    fn get_rook_from(&self) -> Option<Position> {
+        self.rook.map(|(from, _)| from.into())
+    }
+
+    fn get_rook_to(&self) -> Option<Position> {
+        self.rook.map(|(_, to)| to.into())
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn inspect(&self) -> String {
+        format!("#<Silverpoint::MoveInfo {}>", self.m)
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("MoveInfo", ruby.class_object())?;
+    class.define_method("move", method!(MoveInfo::get_move, 0))?;
+    class.define_method("piece", method!(MoveInfo::get_piece, 0))?;
+    class.define_method("from", method!(MoveInfo::get_from, 0))?;
+    class.define_method("to", method!(MoveInfo::get_to, 0))?;
+    class.define_method("captured", method!(MoveInfo::get_captured, 0))?;
+    class.define_method("capture_square", method!(MoveInfo::get_capture_square, 0))?;
+    class.define_method("capture?", method!(MoveInfo::is_capture, 0))?;
+    class.define_method("check?", method!(MoveInfo::is_check, 0))?;
+    class.define_method("mate?", method!(MoveInfo::is_mate, 0))?;
+    class.define_method("castle?", method!(MoveInfo::is_castle, 0))?;
+    class.define_method("en_passant?", method!(MoveInfo::is_en_passant, 0))?;
+    class.define_method("promotion", method!(MoveInfo::get_promotion, 0))?;
+    class.define_method("rook_from", method!(MoveInfo::get_rook_from, 0))?;
+    class.define_method("rook_to", method!(MoveInfo::get_rook_to, 0))?;
+
+    class.define_method("dup", method!(MoveInfo::dup, 0))?;
+    class.define_method("clone", method!(MoveInfo::dup, 0))?;
+
+    class.define_method("inspect", method!(MoveInfo::inspect, 0))?;
+    class.define_method("==", method!(MoveInfo::eq, 1))?;
+    class.define_method("!=", method!(MoveInfo::ne, 1))?;
+
+    Ok(())
+}
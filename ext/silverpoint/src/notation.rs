@@ -0,0 +1,413 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::{Color, Evaluate, Move, Piece, Position};
+
+use crate::{
+    board::{is_mate, legal_moves},
+    errors::ParseError,
+};
+
+const SUGGESTIONS: usize = 3;
+
+fn letter(piece: Piece) -> char {
+    match piece {
+        Piece::Pawn(..) => 'P',
+        Piece::Knight(..) => 'N',
+        Piece::Bishop(..) => 'B',
+        Piece::Rook(..) => 'R',
+        Piece::Queen(..) => 'Q',
+        Piece::King(..) => 'K',
+    }
+}
+
+/// The squares a move on `board` goes from and to, with castles as the king's move, and the
+/// piece making it.
+fn squares(board: &chess_engine::Board, m: Move) -> Option<(Position, Position, Piece)> {
+    let king = Position::king_pos(board.get_turn_color());
+    let (from, to) = match m {
+        Move::Piece(from, to) => (from, to),
+        Move::KingSideCastle => (king, king.next_right().next_right()),
+        Move::QueenSideCastle => (king, king.next_left().next_left()),
+        Move::Resign => return None,
+    };
+
+    Some((from, to, board.get_piece(from)?))
+}
+
+fn promotes(piece: Piece, to: Position) -> bool {
+    match piece {
+        Piece::Pawn(Color::White, _) => to.get_row() == 7,
+        Piece::Pawn(Color::Black, _) => to.get_row() == 0,
+        _ => false,
+    }
+}
+
+/// Write a legal move in standard algebraic notation, like `Nbd2`, `exd6`, `e8=Q+` or
+/// `O-O#`.
+pub fn san(board: &chess_engine::Board, m: Move) -> String {
+    let mut san = match m {
+        Move::KingSideCastle => "O-O".to_owned(),
+        Move::QueenSideCastle => "O-O-O".to_owned(),
+        Move::Resign => return "resign".to_owned(),
+        Move::Piece(from, to) => {
+            let Some(piece) = board.get_piece(from) else {
+                return format!("{from}{to}");
+            };
+            let capture = board.has_enemy_piece(to, piece.get_color())
+                || (matches!(piece, Piece::Pawn(..)) && from.get_col() != to.get_col());
+
+            let mut san = String::new();
+            if matches!(piece, Piece::Pawn(..)) {
+                if capture {
+                    san.push_str(&from.to_string()[..1]);
+                }
+            } else {
+                san.push(letter(piece));
+
+                // Name the file, the rank or both, whichever tells this piece apart from any
+                // others of its kind that could move to the same square.
+                let rivals = legal_moves(board)
+                    .into_iter()
+                    .filter_map(|other| squares(board, other))
+                    .filter(|&(other, other_to, other_piece)| {
+                        other != from && other_to == to && letter(other_piece) == letter(piece)
+                    })
+                    .map(|(other, _, _)| other)
+                    .collect::<Vec<_>>();
+                let square = from.to_string();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|other| other.get_col() != from.get_col()) {
+                        san.push_str(&square[..1]);
+                    } else if rivals.iter().all(|other| other.get_row() != from.get_row()) {
+                        san.push_str(&square[1..]);
+                    } else {
+                        san.push_str(&square);
+                    }
+                }
+            }
+
+            if capture {
+                san.push('x');
+            }
+            san.push_str(&to.to_string());
+            if promotes(piece, to) {
+                san.push_str("=Q");
+            }
+            san
+        }
+    };
+
+    let next = board.apply_eval_move(m);
+    if is_mate(&next) {
+        san.push('#');
+    } else if next.is_in_check(next.get_turn_color()) {
+        san.push('+');
+    }
+
+    san
+}
+
+/// What a player wrote, with whatever they left out as `None`.
+enum Written {
+    /// Castling, on the side given if any.
+    Castle { kingside: Option<bool> },
+    Piece {
+        piece: Option<char>,
+        from_col: Option<i32>,
+        from_row: Option<i32>,
+        to: Position,
+        promotion: Option<char>,
+    },
+}
+
+impl Written {
+    /// Whether a legal move fits what was written. Without a piece letter pawns are meant,
+    /// unless `any_piece` or the whole origin square is given, as in UCI.
+    fn fits(&self, board: &chess_engine::Board, m: Move, any_piece: bool) -> bool {
+        let Some((from, to, piece)) = squares(board, m) else {
+            return false;
+        };
+
+        match *self {
+            Self::Castle { kingside } => match m {
+                Move::KingSideCastle => kingside != Some(false),
+                Move::QueenSideCastle => kingside != Some(true),
+                _ => false,
+            },
+            Self::Piece {
+                piece: letter_written,
+                from_col,
+                from_row,
+                to: to_written,
+                promotion,
+            } => {
+                // A castle can also be written as the king taking its own rook, as in
+                // Chess960.
+                let rook = match m {
+                    Move::KingSideCastle => Some(Position::new(from.get_row(), 7)),
+                    Move::QueenSideCastle => Some(Position::new(from.get_row(), 0)),
+                    _ => None,
+                };
+                let piece_fits = match letter_written {
+                    Some(written) => letter(piece) == written,
+                    None => {
+                        any_piece
+                            || (from_col.is_some() && from_row.is_some())
+                            || matches!(piece, Piece::Pawn(..))
+                    }
+                };
+
+                (to_written == to || Some(to_written) == rook)
+                    && piece_fits
+                    && from_col.is_none_or(|col| col == from.get_col())
+                    && from_row.is_none_or(|row| row == from.get_row())
+                    // `chess_engine` only promotes to queens.
+                    && promotion.is_none_or(|promotion| promotion == 'Q' && promotes(piece, to))
+            }
+        }
+    }
+
+    /// How close a legal move comes to what was written, for suggesting moves.
+    fn nearness(&self, board: &chess_engine::Board, m: Move) -> u32 {
+        let Some((from, to, piece)) = squares(board, m) else {
+            return 0;
+        };
+
+        match *self {
+            Self::Castle { .. } => {
+                u32::from(matches!(m, Move::KingSideCastle | Move::QueenSideCastle))
+            }
+            Self::Piece {
+                piece: letter_written,
+                from_col,
+                from_row,
+                to: to_written,
+                ..
+            } => {
+                let distance = (to.get_col() - to_written.get_col())
+                    .abs()
+                    .max((to.get_row() - to_written.get_row()).abs());
+                let piece = letter_written.map_or(matches!(piece, Piece::Pawn(..)), |written| {
+                    letter(piece) == written
+                });
+
+                2 * u32::from(to == to_written)
+                    + u32::from(distance == 1)
+                    + 3 * u32::from(piece)
+                    + u32::from(from_col == Some(from.get_col()))
+                    + u32::from(from_row == Some(from.get_row()))
+            }
+        }
+    }
+
+    /// The move this pins down by itself, if both squares were written.
+    fn as_move(&self) -> Option<Move> {
+        match *self {
+            Self::Castle {
+                kingside: Some(true),
+            } => Some(Move::KingSideCastle),
+            Self::Castle {
+                kingside: Some(false),
+            } => Some(Move::QueenSideCastle),
+            Self::Piece {
+                from_col: Some(col),
+                from_row: Some(row),
+                to,
+                ..
+            } => Some(Move::Piece(Position::new(row, col), to)),
+            Self::Castle { kingside: None } | Self::Piece { .. } => None,
+        }
+    }
+}
+
+fn castling(input: impl Iterator<Item = char>) -> Option<Written> {
+    let squashed = input
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+
+    let kingside = match squashed.as_str() {
+        "castle" | "castles" => None,
+        "oo" | "00" | "castlekingside" | "kingsidecastle" | "castleshort" | "shortcastle" => {
+            Some(true)
+        }
+        "ooo" | "000" | "castlequeenside" | "queensidecastle" | "castlelong" | "longcastle" => {
+            Some(false)
+        }
+        _ => return None,
+    };
+
+    Some(Written::Castle { kingside })
+}
+
+/// Each character of `input` that means something, with its column, leaving out a move
+/// number in front, separators, capture marks and annotations.
+fn significant_chars(input: &str) -> Vec<(usize, char)> {
+    // A move number looks like `12.` or `12...`.
+    let number = input
+        .chars()
+        .take_while(|c| c.is_whitespace() || c.is_ascii_digit())
+        .count();
+    let dots = input.chars().skip(number).take_while(|&c| c == '.').count();
+    let skip = if dots > 0 { number + dots } else { 0 };
+
+    let mut chars = input
+        .chars()
+        .enumerate()
+        .skip(skip)
+        .filter_map(|(column, c)| {
+            let c = match c {
+                '♔' | '♚' => 'K',
+                '♕' | '♛' => 'Q',
+                '♖' | '♜' => 'R',
+                '♗' | '♝' => 'B',
+                '♘' | '♞' => 'N',
+                '♙' | '♟' => 'P',
+                c if c.is_whitespace() => return None,
+                '-' | 'x' | 'X' | ':' | '×' | '=' | '(' | ')' | '/' | '.' | '+' | '#' | '!'
+                | '?' => return None,
+                c => c,
+            };
+            Some((column, c))
+        })
+        .collect::<Vec<_>>();
+
+    // An en passant capture can be marked with `e.p.`, which is `ep` without the dots.
+    if let [.., (_, 'e' | 'E'), (_, 'p' | 'P')] = chars[..] {
+        chars.truncate(chars.len() - 2);
+    }
+
+    chars
+}
+
+fn read(input: &str) -> Result<Written, ParseError> {
+    let mut chars = significant_chars(input);
+    if let Some(castle) = castling(chars.iter().map(|&(_, c)| c)) {
+        return Ok(castle);
+    }
+
+    let file = |c: char| match c.to_ascii_lowercase() {
+        file @ 'a'..='h' => Some(i32::from(u8::try_from(file).ok()? - b'a')),
+        _ => None,
+    };
+    let rank = |c: char| match c {
+        rank @ '1'..='8' => Some(i32::from(u8::try_from(rank).ok()? - b'1')),
+        _ => None,
+    };
+
+    let promotion = match chars[..] {
+        [.., (_, before), (_, c)] if before.is_ascii_digit() && "QRBNqrbn".contains(c) => {
+            chars.pop();
+            Some(c.to_ascii_uppercase())
+        }
+        _ => None,
+    };
+
+    let end = input.chars().count();
+    let column_at = |i: usize| chars.get(i).map_or(end, |&(column, _)| column);
+    let to = match chars[..] {
+        [.., (_, f), (_, r)] => match (file(f), rank(r)) {
+            (Some(col), Some(row)) => Position::new(row, col),
+            (Some(_), None) => {
+                return Err(ParseError::new(
+                    input,
+                    column_at(chars.len() - 1),
+                    "rank 1-8",
+                ))
+            }
+            (None, _) => {
+                return Err(ParseError::new(
+                    input,
+                    column_at(chars.len() - 2),
+                    "file a-h",
+                ))
+            }
+        },
+        _ => return Err(ParseError::new(input, column_at(0), "square")),
+    };
+    chars.truncate(chars.len() - 2);
+
+    let mut rest = &chars[..];
+    // A lowercase `b` is the b-file, as in SAN, but other piece letters can be lowercase.
+    let piece = match rest {
+        [(_, c @ ('K' | 'Q' | 'R' | 'B' | 'N' | 'P' | 'k' | 'q' | 'r' | 'n')), ..] => {
+            rest = &rest[1..];
+            Some(c.to_ascii_uppercase())
+        }
+        _ => None,
+    };
+    let from_col = match rest {
+        [(_, c), ..] if file(*c).is_some() => {
+            rest = &rest[1..];
+            file(*c)
+        }
+        _ => None,
+    };
+    let from_row = match rest {
+        [(_, c), ..] if rank(*c).is_some() => {
+            rest = &rest[1..];
+            rank(*c)
+        }
+        _ => None,
+    };
+    if let [(column, _), ..] = rest {
+        return Err(ParseError::new(input, *column, "piece or square"));
+    }
+
+    Ok(Written::Piece {
+        piece,
+        from_col,
+        from_row,
+        to,
+        promotion,
+    })
+}
+
+pub enum Unresolved {
+    /// It isn't a move at all.
+    Unreadable(ParseError),
+    /// More than one legal move fits.
+    Ambiguous(Vec<Move>),
+    /// No legal move fits. Has the move if the input pins one down, and the nearest legal
+    /// moves, nearest first.
+    Illegal(Option<Move>, Vec<Move>),
+}
+
+pub fn resolve(board: &chess_engine::Board, input: &str) -> Result<Move, Unresolved> {
+    let written = read(input).map_err(Unresolved::Unreadable)?;
+    let moves = legal_moves(board);
+
+    let fitting = |any_piece: bool| {
+        moves
+            .iter()
+            .copied()
+            .filter(|&m| written.fits(board, m, any_piece))
+            .collect::<Vec<_>>()
+    };
+    // Leaving the piece letter off is common enough to allow, when no pawn move fits.
+    let mut fits = fitting(false);
+    if fits.is_empty() {
+        fits = fitting(true);
+    }
+
+    match fits[..] {
+        [m] => Ok(m),
+        [] => {
+            let mut near = moves
+                .iter()
+                .map(|&m| (written.nearness(board, m), m))
+                .filter(|&(nearness, _)| nearness > 0)
+                .collect::<Vec<_>>();
+            near.sort_by_key(|&(nearness, _)| std::cmp::Reverse(nearness));
+
+            Err(Unresolved::Illegal(
+                written.as_move(),
+                near.into_iter().take(SUGGESTIONS).map(|(_, m)| m).collect(),
+            ))
+        }
+        _ => Err(Unresolved::Ambiguous(fits)),
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Pawns are kept as bitboards, one bit per square from a1, and files as one bit per file
+//! from a.
+
+use chess_engine::{Color, Piece};
+use magnus::method;
+use magnus::Module;
+
+use crate::attacks;
+use crate::codec::color_name;
+use crate::enums;
+use crate::frozen::frozen_wrap;
+use crate::geometry;
+use crate::position::from_square_index;
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+fn file_squares(file: usize) -> u64 {
+    FILE_A << file
+}
+
+fn neighbouring_files(file: usize) -> u64 {
+    let west = if file > 0 { file_squares(file - 1) } else { 0 };
+    let east = if file < 7 { file_squares(file + 1) } else { 0 };
+    west | east
+}
+
+/// The squares on the ranks in front of `rank`, seen from white's side if `white`.
+fn ranks_ahead(rank: usize, white: bool) -> u64 {
+    (0..8)
+        .filter(|&other| if white { other > rank } else { other < rank })
+        .fold(0, |bits, other| bits | 0xff << (8 * other))
+}
+
+fn files(pawns: u64) -> u8 {
+    (0..8)
+        .filter(|&file| pawns & file_squares(file) != 0)
+        .fold(0, |files, file| files | 1 << file)
+}
+
+fn squares(bits: u64) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |&square| bits & 1 << square != 0)
+}
+
+/// The pawns of `color` and then those of the other side.
+fn pawns(board: &chess_engine::Board, color: Color) -> (u64, u64) {
+    attacks::pieces(board).fold((0, 0), |(own, enemy), (square, piece)| match piece {
+        Piece::Pawn(pawn_color, _) if pawn_color == color => (own | 1 << square, enemy),
+        Piece::Pawn(..) => (own, enemy | 1 << square),
+        _ => (own, enemy),
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PawnStructure {
+    color: Color,
+    /// With no pawns of the other side in front of them on their file or those either side.
+    passed: u64,
+    /// With no pawns of their own on the files either side.
+    isolated: u64,
+    /// Sharing their file with another of their own pawns.
+    doubled: u64,
+    /// Left behind by the pawns either side, and unable to step up without being taken.
+    backward: u64,
+    /// Beside or diagonally next to another of their own pawns.
+    connected: u64,
+    islands: u8,
+    open_files: u8,
+    half_open_files: u8,
+}
+
+frozen_wrap!(PawnStructure, "Silverpoint::PawnStructure");
+
+impl PawnStructure {
+    pub(crate) fn new(board: &chess_engine::Board, color: Color) -> Self {
+        let white = color == Color::White;
+        let (own, enemy) = pawns(board, color);
+        let mut structure = Self {
+            color,
+            passed: 0,
+            isolated: 0,
+            doubled: 0,
+            backward: 0,
+            connected: 0,
+            islands: 0,
+            open_files: 0,
+            half_open_files: 0,
+        };
+
+        for square in squares(own) {
+            let (rank, file) = (square / 8, square % 8);
+            let pawn = 1 << square;
+            let ahead = ranks_ahead(rank, white);
+            let neighbours = own & neighbouring_files(file);
+
+            if enemy & (file_squares(file) | neighbouring_files(file)) & ahead == 0 {
+                structure.passed |= pawn;
+            }
+            if neighbours == 0 {
+                structure.isolated |= pawn;
+            }
+            if own & file_squares(file) & !pawn != 0 {
+                structure.doubled |= pawn;
+            }
+            if neighbours & geometry::king_attacks(square) != 0 {
+                structure.connected |= pawn;
+            }
+
+            // An enemy pawn covering the square in front attacks it from where a pawn of ours
+            // standing there would attack.
+            let stop = if white {
+                square.checked_add(8).filter(|&stop| stop < 64)
+            } else {
+                square.checked_sub(8)
+            };
+            let stop_covered =
+                stop.is_some_and(|stop| geometry::pawn_attacks(stop, white) & enemy != 0);
+            if neighbours != 0 && neighbours & !ahead == 0 && stop_covered {
+                structure.backward |= pawn;
+            }
+        }
+
+        let (own_files, enemy_files) = (files(own), files(enemy));
+        // Count the files that start a run of files with pawns.
+        structure.islands =
+            u8::try_from((own_files & !(own_files << 1)).count_ones()).unwrap_or_default();
+        structure.open_files = !(own_files | enemy_files);
+        structure.half_open_files = !own_files & enemy_files;
+
+        structure
+    }
+
+    /// Bonuses and penalties for the structure, on `value_for`'s scale where a pawn is 10.
+    pub(crate) fn score(&self) -> f64 {
+        let count = |bits: u64| f64::from(bits.count_ones());
+
+        // Passed pawns are worth more the closer they are to queening.
+        let advance = squares(self.passed)
+            .map(|square| {
+                let rank = square / 8;
+                if self.color == Color::White {
+                    rank.saturating_sub(1)
+                } else {
+                    6_usize.saturating_sub(rank)
+                }
+            })
+            .sum::<usize>();
+        // Advances are at most 6 ranks per pawn, so the cast is lossless.
+        #[allow(clippy::cast_precision_loss)]
+        let advance = advance as f64;
+
+        2.0 * count(self.passed) + 1.5 * advance
+            - 1.5 * count(self.isolated)
+            - count(self.doubled)
+            - count(self.backward)
+            + 0.5 * count(self.connected)
+            - f64::from(self.islands.saturating_sub(1))
+    }
+
+    fn pieces(&self, bits: u64) -> Vec<enums::Piece> {
+        squares(bits)
+            .map(|square| Piece::Pawn(self.color, from_square_index(square)).into())
+            .collect()
+    }
+
+    fn file_names(files: u8) -> Vec<&'static str> {
+        (0..8)
+            .filter(|&file| files & 1 << file != 0)
+            .map(|file| &"abcdefgh"[file..=file])
+            .collect()
+    }
+
+    fn color(&self) -> enums::Color {
+        self.color.into()
+    }
+
+    fn passed(&self) -> Vec<enums::Piece> {
+        self.pieces(self.passed)
+    }
+
+    fn isolated(&self) -> Vec<enums::Piece> {
+        self.pieces(self.isolated)
+    }
+
+    fn doubled(&self) -> Vec<enums::Piece> {
+        self.pieces(self.doubled)
+    }
+
+    fn backward(&self) -> Vec<enums::Piece> {
+        self.pieces(self.backward)
+    }
+
+    fn connected(&self) -> Vec<enums::Piece> {
+        self.pieces(self.connected)
+    }
+
+    fn islands(&self) -> u8 {
+        self.islands
+    }
+
+    fn open_files(&self) -> Vec<&'static str> {
+        Self::file_names(self.open_files)
+    }
+
+    fn half_open_files(&self) -> Vec<&'static str> {
+        Self::file_names(self.half_open_files)
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::PawnStructure {} passed={} isolated={} doubled={} backward={} \
+             islands={}>",
+            color_name(self.color),
+            self.passed.count_ones(),
+            self.isolated.count_ones(),
+            self.doubled.count_ones(),
+            self.backward.count_ones(),
+            self.islands
+        )
+    }
+}
+
+/// The pawn structure and rooks on open files for `color`, less the same for the other
+/// side, on `value_for`'s scale.
+pub(crate) fn evaluate(board: &chess_engine::Board, color: Color) -> f64 {
+    let side = |color: Color| {
+        let structure = PawnStructure::new(board, color);
+        let rooks = attacks::pieces(board)
+            .filter(
+                |&(_, piece)| matches!(piece, Piece::Rook(rook_color, _) if rook_color == color),
+            )
+            .map(|(square, _)| match 1 << (square % 8) {
+                file if structure.open_files & file != 0 => 1.5,
+                file if structure.half_open_files & file != 0 => 0.75,
+                _ => 0.0,
+            })
+            .sum::<f64>();
+
+        structure.score() + rooks
+    };
+
+    side(color) - side(!color)
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("PawnStructure", ruby.class_object())?;
+    class.define_method("color", method!(PawnStructure::color, 0))?;
+    class.define_method("passed", method!(PawnStructure::passed, 0))?;
+    class.define_method("isolated", method!(PawnStructure::isolated, 0))?;
+    class.define_method("doubled", method!(PawnStructure::doubled, 0))?;
+    class.define_method("backward", method!(PawnStructure::backward, 0))?;
+    class.define_method("connected", method!(PawnStructure::connected, 0))?;
+    class.define_method("islands", method!(PawnStructure::islands, 0))?;
+    class.define_method("open_files", method!(PawnStructure::open_files, 0))?;
+    class.define_method(
+        "half_open_files",
+        method!(PawnStructure::half_open_files, 0),
+    )?;
+    class.define_method("score", method!(PawnStructure::score, 0))?;
+
+    class.define_method("dup", method!(PawnStructure::dup, 0))?;
+    class.define_method("clone", method!(PawnStructure::dup, 0))?;
+
+    class.define_method("inspect", method!(PawnStructure::inspect, 0))?;
+    class.define_method("==", method!(PawnStructure::eq, 1))?;
+    class.define_method("!=", method!(PawnStructure::ne, 1))?;
+
+    Ok(())
+}
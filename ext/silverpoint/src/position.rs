@@ -3,21 +3,104 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+use std::cmp::Ordering;
+
+use magnus::block::Yield;
 use magnus::function;
 use magnus::method;
+use magnus::value::ReprValue;
 use magnus::Module;
 use magnus::Object;
-
+use magnus::RClass;
+use magnus::RHash;
+use magnus::RString;
+use magnus::Symbol;
+use magnus::TryConvert;
+use magnus::Value;
+
+use crate::codec;
 use crate::enums;
 use crate::enums::Color;
+use crate::errors;
+use crate::errors::ParseError;
+use crate::frozen::frozen_wrap;
+use crate::geometry;
+use crate::geometry::Direction;
 
-#[magnus::wrap(class = "Silverpoint::Position", size, free_immediately)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Position(chess_engine::Position);
 
+frozen_wrap!(Position, "Silverpoint::Position");
+
 unsafe impl magnus::IntoValueFromNative for Position {}
 
+/// The 0..64 index of a position, counting from a1 to h8 rank by rank.
+pub(crate) fn square_index(pos: chess_engine::Position) -> usize {
+    usize::try_from(pos.get_row() * 8 + pos.get_col()).expect("position is off the board")
+}
+
+/// The position at a 0..64 index, the inverse of [`square_index`].
+pub(crate) fn from_square_index(index: usize) -> chess_engine::Position {
+    let index = i32::try_from(index).expect("square index is off the board");
+    chess_engine::Position::new(index / 8, index % 8)
+}
+
+/// Parse a square like `e4` from the `len` characters at the 0-based character `column` of
+/// `input`.
+///
+/// Unlike `chess_engine::Position::pgn`, ranks off the board are rejected.
+pub(crate) fn parse_square(
+    input: &str,
+    column: usize,
+    len: usize,
+) -> Result<chess_engine::Position, magnus::Error> {
+    let square = input.chars().skip(column).take(len).collect::<String>();
+    let mut chars = square.chars();
+
+    let file = chars
+        .next()
+        .and_then(|c| u8::try_from(c.to_ascii_lowercase()).ok());
+    let col = match file {
+        Some(file @ b'a'..=b'h') => i32::from(file - b'a'),
+        _ => return Err(ParseError::new(input, column, "file a-h").into()),
+    };
+
+    let digits = chars
+        .clone()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    if digits.is_empty() {
+        return Err(ParseError::new(input, column + 1, "rank 1-8").into());
+    }
+    if chars.nth(digits.len()).is_some() {
+        return Err(ParseError::new(input, column + 1 + digits.len(), "end of square").into());
+    }
+
+    let row = digits.parse::<i32>().unwrap_or(i32::MAX);
+    if !(1..=8).contains(&row) {
+        return Err(errors::invalid_position(&square, row - 1, col));
+    }
+
+    Ok(chess_engine::Position::new(row - 1, col))
+}
+
+/// Find where `chess_engine::Position::pgn` gave up on `str`, which it doesn't say.
+fn pgn_error(str: &str) -> magnus::Error {
+    let column = str.chars().take_while(|c| c.is_whitespace()).count();
+    let len = str.trim().chars().count();
+
+    if let Some(end) = str.trim().chars().position(char::is_whitespace) {
+        return parse_square(str, column, end)
+            .err()
+            .unwrap_or_else(|| ParseError::new(str, column + end, "end of input").into());
+    }
+
+    parse_square(str, column, len)
+        .err()
+        .unwrap_or_else(|| ParseError::new(str, column, "square").into())
+}
+
 impl From<chess_engine::Position> for Position {
     fn from(value: chess_engine::Position) -> Self {
         Self(value)
@@ -46,10 +129,7 @@ impl Position {
     fn pgn(str: String) -> Result<Self, magnus::Error> {
         chess_engine::Position::pgn(&str)
             .map(Into::into)
-            .map_err(|err| {
-                let ruby = magnus::Ruby::get().unwrap();
-                magnus::Error::new(ruby.exception_runtime_error(), err)
-            })
+            .map_err(|_| pgn_error(&str))
     }
 
     fn is_on_board(&self) -> bool {
@@ -152,6 +232,157 @@ impl Position {
         self.0.is_knight_move(other.0)
     }
 
+    fn square(self) -> Result<usize, magnus::Error> {
+        if self.0.is_off_board() {
+            return Err(errors::invalid_position(
+                &self.inspect(),
+                self.0.get_row(),
+                self.0.get_col(),
+            ));
+        }
+
+        Ok(square_index(self.0))
+    }
+
+    fn at(square: usize) -> Self {
+        from_square_index(square).into()
+    }
+
+    fn from_index(index: i64) -> Result<Self, magnus::Error> {
+        usize::try_from(index)
+            .ok()
+            .filter(|&index| index < 64)
+            .map(Self::at)
+            .ok_or_else(|| {
+                let ruby = magnus::Ruby::get().unwrap();
+                magnus::Error::new(
+                    ruby.exception_arg_error(),
+                    format!("square index {index} is off the board, expected 0..63"),
+                )
+            })
+    }
+
+    fn all() -> Vec<Self> {
+        (0..64).map(Self::at).collect()
+    }
+
+    fn each(ruby: &magnus::Ruby, rb_self: RClass) -> Yield<impl Iterator<Item = Self>> {
+        if ruby.block_given() {
+            Yield::Iter((0..64).map(Self::at))
+        } else {
+            Yield::Enumerator(rb_self.enumeratorize("each", ()))
+        }
+    }
+
+    fn index(&self) -> Result<usize, magnus::Error> {
+        self.square()
+    }
+
+    fn file(&self) -> Result<&'static str, magnus::Error> {
+        let file = self.square()? % 8;
+        Ok(&"abcdefgh"[file..=file])
+    }
+
+    fn rank(&self) -> Result<usize, magnus::Error> {
+        Ok(self.square()? / 8 + 1)
+    }
+
+    fn square_color(&self) -> Result<Color, magnus::Error> {
+        let color = if geometry::is_light(self.square()?) {
+            chess_engine::Color::White
+        } else {
+            chess_engine::Color::Black
+        };
+
+        Ok(color.into())
+    }
+
+    fn between(&self, &other: &Self) -> Result<Vec<Self>, magnus::Error> {
+        Ok(geometry::between(self.square()?, other.square()?)
+            .map(Self::at)
+            .collect())
+    }
+
+    fn ray(&self, direction: Symbol) -> Result<Vec<Self>, magnus::Error> {
+        let name = direction.name()?;
+        let direction = Direction::from_name(&name).ok_or_else(|| {
+            let ruby = magnus::Ruby::get().unwrap();
+            magnus::Error::new(
+                ruby.exception_arg_error(),
+                format!("unknown direction :{name}"),
+            )
+        })?;
+
+        Ok(geometry::ray(self.square()?, direction)
+            .map(Self::at)
+            .collect())
+    }
+
+    fn distance(&self, &other: &Self) -> Result<u8, magnus::Error> {
+        Ok(geometry::distance(self.square()?, other.square()?))
+    }
+
+    fn manhattan_distance(&self, &other: &Self) -> Result<u8, magnus::Error> {
+        Ok(geometry::manhattan(self.square()?, other.square()?))
+    }
+
+    fn mirror(&self) -> Result<Self, magnus::Error> {
+        Ok(Self::at(geometry::mirror(self.square()?)))
+    }
+
+    fn flip(&self) -> Result<Self, magnus::Error> {
+        Ok(Self::at(geometry::flip(self.square()?)))
+    }
+
+    /// The next position from a1 to h8, so ranges of positions can be walked.
+    fn succ(&self) -> Result<Option<Self>, magnus::Error> {
+        let next = self.square()? + 1;
+        Ok((next < 64).then(|| Self::at(next)))
+    }
+
+    fn compare(&self, other: Value) -> Option<i8> {
+        let other = <&Self>::try_convert(other).ok()?;
+
+        Some(match self.cmp(other) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        })
+    }
+
+    fn dump(&self, _level: i32) -> Result<RString, magnus::Error> {
+        let square = codec::encode_position(self.0).ok_or_else(|| codec::off_board(self.0))?;
+        Ok(codec::binary_string(&[square]))
+    }
+
+    fn load(dump: RString) -> Result<Self, magnus::Error> {
+        match codec::string_bytes(dump)[..] {
+            [square] => codec::decode_position(square)
+                .map(Self)
+                .ok_or_else(|| codec::invalid_dump("Position")),
+            _ => Err(codec::invalid_dump("Position")),
+        }
+    }
+
+    fn dup(&self) -> Self {
+        *self
+    }
+
+    fn to_h(&self) -> Result<RHash, magnus::Error> {
+        let hash = magnus::Ruby::get().unwrap().hash_new();
+        hash.aset(Symbol::new("row"), self.0.get_row())?;
+        hash.aset(Symbol::new("col"), self.0.get_col())?;
+
+        Ok(hash)
+    }
+
+    fn from_h(hash: RHash) -> Result<Self, magnus::Error> {
+        Ok(Self::new(
+            codec::field(hash, "row")?,
+            codec::field(hash, "col")?,
+        ))
+    }
+
     fn to_string(&self) -> String {
         format!("{}", self.0)
     }
@@ -168,6 +399,12 @@ pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Erro
     class.define_singleton_method("queen_pos", function!(Position::queen_pos, 1))?;
     class.define_singleton_method("new", function!(Position::new, 2))?;
     class.define_singleton_method("pgn", function!(Position::pgn, 1))?;
+    class.define_singleton_method("_load", function!(Position::load, 1))?;
+    class.define_singleton_method("from_h", function!(Position::from_h, 1))?;
+    class.define_singleton_method("from_index", function!(Position::from_index, 1))?;
+    class.define_singleton_method("all", function!(Position::all, 0))?;
+    class.define_singleton_method("each", method!(Position::each, 0))?;
+    class.extend_object(ruby.module_enumerable())?;
 
     class.define_method("on_board?", method!(Position::is_on_board, 0))?;
     class.define_method("off_board?", method!(Position::is_off_board, 0))?;
@@ -192,11 +429,32 @@ pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Erro
     class.define_method("diagonals_to", method!(Position::diagonals_to, 1))?;
     class.define_method("orthogonals_to", method!(Position::orthogonals_to, 1))?;
     class.define_method("knight_move?", method!(Position::is_knight_move, 1))?;
+    class.define_method("index", method!(Position::index, 0))?;
+    class.define_method("file", method!(Position::file, 0))?;
+    class.define_method("rank", method!(Position::rank, 0))?;
+    class.define_method("square_color", method!(Position::square_color, 0))?;
+    class.define_method("between", method!(Position::between, 1))?;
+    class.define_method("ray", method!(Position::ray, 1))?;
+    class.define_method("distance", method!(Position::distance, 1))?;
+    class.define_method(
+        "manhattan_distance",
+        method!(Position::manhattan_distance, 1),
+    )?;
+    class.define_method("mirror", method!(Position::mirror, 0))?;
+    class.define_method("flip", method!(Position::flip, 0))?;
+    class.define_method("succ", method!(Position::succ, 0))?;
+
+    class.define_method("_dump", method!(Position::dump, 1))?;
+    class.define_method("dup", method!(Position::dup, 0))?;
+    class.define_method("clone", method!(Position::dup, 0))?;
+    class.define_method("to_h", method!(Position::to_h, 0))?;
 
     class.define_method("to_s", method!(Position::to_string, 0))?;
     class.define_method("inspect", method!(Position::inspect, 0))?;
     class.define_method("==", method!(Position::eq, 1))?;
     class.define_method("!=", method!(Position::ne, 1))?;
+    class.define_method("<=>", method!(Position::compare, 1))?;
+    class.include_module(ruby.module_comparable())?;
 
     bind_constants(class)?;
 
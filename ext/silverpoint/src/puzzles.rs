@@ -0,0 +1,145 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::Evaluate;
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    Module, Object, RArray, RHash, TryConvert, Value,
+};
+
+use crate::{
+    board::{ranked_moves, Board},
+    enums::Move,
+    fen,
+    gvl::{self, no_gvl},
+};
+
+#[magnus::wrap(class = "Silverpoint::Puzzle", size, free_immediately)]
+pub struct Puzzle {
+    index: usize,
+    board: chess_engine::Board,
+    solution: Vec<chess_engine::Move>,
+    margin: f64,
+}
+
+unsafe impl magnus::IntoValueFromNative for Puzzle {}
+
+/// A puzzle from `board` if exactly one move beats every other by at least `margin` and
+/// leaves the side to move at least `winning` ahead.
+fn scan(
+    index: usize,
+    board: &chess_engine::Board,
+    depth: i32,
+    margin: f64,
+    winning: f64,
+    length: usize,
+) -> Option<Puzzle> {
+    let moves = ranked_moves(board, depth);
+    let [(best, best_value), (_, second_value), ..] = moves[..] else {
+        // With one legal move there is nothing to find.
+        return None;
+    };
+    if best_value < winning || best_value - second_value < margin {
+        return None;
+    }
+
+    // Play out the rest of the line with both sides picking their best moves.
+    let mut solution = vec![best];
+    let mut current = board.apply_eval_move(best);
+    while solution.len() < length {
+        let Some(&(m, _)) = ranked_moves(&current, depth).first() else {
+            break;
+        };
+        solution.push(m);
+        current = current.apply_eval_move(m);
+    }
+
+    Some(Puzzle {
+        index,
+        board: *board,
+        solution,
+        margin: best_value - second_value,
+    })
+}
+
+impl Puzzle {
+    fn find(args: &[Value]) -> Result<Vec<Self>, magnus::Error> {
+        let args = scan_args::<(RArray,), (), (), (), RHash, ()>(args)?;
+        let (boards,) = args.required;
+        #[allow(clippy::type_complexity)]
+        let kwargs = get_kwargs::<_, (), (Option<i32>, Option<f64>, Option<f64>, Option<usize>), ()>(
+            args.keywords,
+            &[],
+            &["depth", "margin", "winning", "length"],
+        )?;
+        let (depth, margin, winning, length) = kwargs.optional;
+        // Pieces are worth ten times their material value, so these are three and two pawns.
+        let (depth, margin, winning, length) = (
+            depth.unwrap_or(2),
+            margin.unwrap_or(30.0),
+            winning.unwrap_or(20.0),
+            length.unwrap_or(3),
+        );
+
+        let boards = boards
+            .into_iter()
+            .map(|value| <&Board>::try_convert(value).map(|&board| board.into()))
+            .collect::<Result<Vec<chess_engine::Board>, _>>()?;
+
+        no_gvl! {
+            boards
+                .iter()
+                .enumerate()
+                .take_while(|_| !gvl::interrupted())
+                .filter_map(|(index, board)| scan(index, board, depth, margin, winning, length))
+                .collect()
+        }
+    }
+
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn board(&self) -> Board {
+        self.board.into()
+    }
+
+    fn fen(&self) -> String {
+        fen::to_fen(&self.board)
+    }
+
+    fn solution(&self) -> Vec<Move> {
+        self.solution.iter().map(|&m| m.into()).collect()
+    }
+
+    fn margin(&self) -> f64 {
+        self.margin
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::Puzzle index={} fen={:?} margin={}>",
+            self.index,
+            self.fen(),
+            self.margin
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let puzzles = module.define_module("Puzzles")?;
+    puzzles.define_singleton_method("find", function!(Puzzle::find, -1))?;
+
+    let class = module.define_class("Puzzle", ruby.class_object())?;
+    class.define_method("index", method!(Puzzle::index, 0))?;
+    class.define_method("board", method!(Puzzle::board, 0))?;
+    class.define_method("fen", method!(Puzzle::fen, 0))?;
+    class.define_method("solution", method!(Puzzle::solution, 0))?;
+    class.define_method("margin", method!(Puzzle::margin, 0))?;
+    class.define_method("inspect", method!(Puzzle::inspect, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::fmt::Write;
+
+use chess_engine::{Color, Piece, Position};
+
+const RESET: &str = "\x1b[0m";
+const LIGHT: &str = "\x1b[48;5;223m";
+const DARK: &str = "\x1b[48;5;137m";
+const LAST_MOVE: &str = "\x1b[48;5;143m";
+const HIGHLIGHT: &str = "\x1b[48;5;167m";
+const WHITE_PIECE: &str = "\x1b[1;97m";
+const BLACK_PIECE: &str = "\x1b[1;30m";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Ascii,
+    Unicode,
+}
+
+pub struct Options {
+    pub style: Style,
+    pub color: bool,
+    pub perspective: Color,
+    pub highlights: Vec<Position>,
+    pub last_move: Option<(Position, Position)>,
+}
+
+fn piece_char(piece: Piece, style: Style, color: bool) -> char {
+    let white = piece.get_color() == Color::White;
+
+    match style {
+        Style::Ascii => {
+            let c = match piece {
+                Piece::King(..) => 'k',
+                Piece::Queen(..) => 'q',
+                Piece::Rook(..) => 'r',
+                Piece::Bishop(..) => 'b',
+                Piece::Knight(..) => 'n',
+                Piece::Pawn(..) => 'p',
+            };
+            if white {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        }
+        // With colors on, both sides use the solid glyphs and are told apart by color.
+        Style::Unicode => {
+            let solid = !white || color;
+            match (piece, solid) {
+                (Piece::King(..), false) => '\u{2654}',
+                (Piece::Queen(..), false) => '\u{2655}',
+                (Piece::Rook(..), false) => '\u{2656}',
+                (Piece::Bishop(..), false) => '\u{2657}',
+                (Piece::Knight(..), false) => '\u{2658}',
+                (Piece::Pawn(..), false) => '\u{2659}',
+                (Piece::King(..), true) => '\u{265A}',
+                (Piece::Queen(..), true) => '\u{265B}',
+                (Piece::Rook(..), true) => '\u{265C}',
+                (Piece::Bishop(..), true) => '\u{265D}',
+                (Piece::Knight(..), true) => '\u{265E}',
+                (Piece::Pawn(..), true) => '\u{265F}',
+            }
+        }
+    }
+}
+
+pub fn render(board: &chess_engine::Board, options: &Options) -> String {
+    let flipped = options.perspective == Color::Black;
+    let order = |i: i32| if flipped { i } else { 7 - i };
+
+    let mut out = String::new();
+    for i in 0..8 {
+        let row = order(i);
+        write!(out, "{} ", row + 1).unwrap();
+
+        for j in 0..8 {
+            let col = 7 - order(j);
+            let pos = Position::new(row, col);
+            let piece = board.get_piece(pos);
+
+            if options.color {
+                let background = if options.highlights.contains(&pos) {
+                    HIGHLIGHT
+                } else if options
+                    .last_move
+                    .is_some_and(|(from, to)| pos == from || pos == to)
+                {
+                    LAST_MOVE
+                } else if (row + col) % 2 == 0 {
+                    DARK
+                } else {
+                    LIGHT
+                };
+                out.push_str(background);
+
+                match piece {
+                    Some(piece) => {
+                        let foreground = match piece.get_color() {
+                            Color::White => WHITE_PIECE,
+                            Color::Black => BLACK_PIECE,
+                        };
+                        let c = piece_char(piece, options.style, true);
+                        write!(out, "{foreground} {c} {RESET}").unwrap();
+                    }
+                    None => write!(out, "   {RESET}").unwrap(),
+                }
+            } else {
+                let c = piece.map_or('.', |piece| piece_char(piece, options.style, false));
+                write!(out, " {c}").unwrap();
+            }
+        }
+        out.push('\n');
+    }
+
+    out.push_str("  ");
+    for j in 0..8 {
+        let file = char::from(b'a' + u8::try_from(7 - order(j)).unwrap());
+        if options.color {
+            write!(out, " {file} ").unwrap();
+        } else {
+            write!(out, " {file}").unwrap();
+        }
+    }
+    out.push('\n');
+
+    out
+}
@@ -0,0 +1,178 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::Evaluate;
+use magnus::{
+    function, method,
+    scan_args::{get_kwargs, scan_args},
+    Module, Object, RArray, RHash, Symbol, TryConvert, Value,
+};
+
+use crate::{
+    board::{ranked_moves, Board},
+    enums::Move,
+    errors,
+    gvl::{self, no_gvl},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Classification {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Classification {
+    /// Classify a move by how much worse it scored than the best move. A pawn is worth 10.
+    fn from_loss(loss: f64) -> Self {
+        match loss {
+            loss if loss <= 0.0 => Self::Best,
+            loss if loss < 5.0 => Self::Good,
+            loss if loss < 10.0 => Self::Inaccuracy,
+            loss if loss < 30.0 => Self::Mistake,
+            _ => Self::Blunder,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Best => "best",
+            Self::Good => "good",
+            Self::Inaccuracy => "inaccuracy",
+            Self::Mistake => "mistake",
+            Self::Blunder => "blunder",
+        }
+    }
+
+    /// The PGN numeric annotation glyph, `$6` (?!), `$2` (?) or `$4` (??).
+    fn nag(self) -> Option<u8> {
+        match self {
+            Self::Best | Self::Good => None,
+            Self::Inaccuracy => Some(6),
+            Self::Mistake => Some(2),
+            Self::Blunder => Some(4),
+        }
+    }
+}
+
+#[magnus::wrap(class = "Silverpoint::Annotation", size, free_immediately)]
+pub struct Annotation {
+    ply: usize,
+    played: chess_engine::Move,
+    best: chess_engine::Move,
+    loss: f64,
+    classification: Classification,
+}
+
+unsafe impl magnus::IntoValueFromNative for Annotation {}
+
+/// Score every move of a game, or report the ply of the first illegal one and the board it
+/// was played on.
+fn review(
+    mut board: chess_engine::Board,
+    moves: &[chess_engine::Move],
+    depth: i32,
+) -> Result<Vec<Annotation>, (usize, chess_engine::Board)> {
+    let mut annotations = Vec::with_capacity(moves.len());
+
+    for (ply, &played) in moves.iter().enumerate() {
+        if gvl::interrupted() {
+            break;
+        }
+
+        let ranked = ranked_moves(&board, depth);
+        let Some(&(_, played_value)) = ranked.iter().find(|&&(m, _)| m == played) else {
+            return Err((ply, board));
+        };
+        let (best, best_value) = ranked[0];
+
+        let classification = if played == best {
+            Classification::Best
+        } else {
+            Classification::from_loss(best_value - played_value)
+        };
+
+        annotations.push(Annotation {
+            ply,
+            played,
+            best,
+            loss: (best_value - played_value).max(0.0),
+            classification,
+        });
+        board = board.apply_eval_move(played);
+    }
+
+    Ok(annotations)
+}
+
+impl Annotation {
+    fn annotate(args: &[Value]) -> Result<Vec<Self>, magnus::Error> {
+        let args = scan_args::<(&Board, RArray), (), (), (), RHash, ()>(args)?;
+        let (&board, moves) = args.required;
+        let kwargs = get_kwargs::<_, (), (Option<i32>,), ()>(args.keywords, &[], &["depth"])?;
+        let (depth,) = kwargs.optional;
+        let depth = depth.unwrap_or(2);
+
+        let moves = moves
+            .into_iter()
+            .map(|value| <&Move>::try_convert(value).map(|&m| m.into()))
+            .collect::<Result<Vec<chess_engine::Move>, _>>()?;
+
+        no_gvl!(review(board.into(), &moves, depth))?.map_err(|(ply, board)| {
+            errors::illegal_move(format!("move {} is illegal", ply + 1), moves[ply], board)
+        })
+    }
+
+    fn ply(&self) -> usize {
+        self.ply
+    }
+
+    fn played(&self) -> Move {
+        self.played.into()
+    }
+
+    fn best(&self) -> Move {
+        self.best.into()
+    }
+
+    fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    fn classification(&self) -> Symbol {
+        Symbol::new(self.classification.name())
+    }
+
+    fn nag(&self) -> Option<u8> {
+        self.classification.nag()
+    }
+
+    fn inspect(&self) -> String {
+        format!(
+            "#<Silverpoint::Annotation ply={} classification={} loss={}>",
+            self.ply,
+            self.classification.name(),
+            self.loss
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let review = module.define_module("Review")?;
+    review.define_singleton_method("annotate", function!(Annotation::annotate, -1))?;
+
+    let class = module.define_class("Annotation", ruby.class_object())?;
+    class.define_method("ply", method!(Annotation::ply, 0))?;
+    class.define_method("move", method!(Annotation::played, 0))?;
+    class.define_method("best_move", method!(Annotation::best, 0))?;
+    class.define_method("loss", method!(Annotation::loss, 0))?;
+    class.define_method("classification", method!(Annotation::classification, 0))?;
+    class.define_method("nag", method!(Annotation::nag, 0))?;
+    class.define_method("inspect", method!(Annotation::inspect, 0))?;
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+use chess_engine::{Evaluate, Move};
+
+use crate::{attacks, board::legal_moves, gvl, pawns};
+
+const OVERHEAD: Duration = Duration::from_millis(50);
+
+pub struct TimeControl {
+    pub time: Duration,
+    pub increment: Duration,
+    pub moves_to_go: Option<u32>,
+}
+
+/// How long to think for: a search won't start a depth it doesn't expect to finish by
+/// `soft`, and abandons one that runs past `hard`.
+#[derive(Clone, Copy)]
+pub struct Budget {
+    pub soft: Duration,
+    pub hard: Duration,
+}
+
+fn complexity(board: &chess_engine::Board, moves: &[Move]) -> f64 {
+    let color = board.get_turn_color();
+    let captures = moves
+        .iter()
+        .filter(|m| matches!(m, Move::Piece(_, to) if board.has_enemy_piece(*to, color)))
+        .count();
+    let check = if board.is_in_check(color) { 0.2 } else { 0.0 };
+
+    // Move counts are tiny, so the casts are lossless.
+    #[allow(clippy::cast_precision_loss)]
+    let activity = (moves.len() + 2 * captures) as f64 / 35.0;
+
+    (activity + check).clamp(0.5, 1.5)
+}
+
+pub fn budget(board: &chess_engine::Board, control: &TimeControl) -> Budget {
+    let usable = control.time.saturating_sub(OVERHEAD);
+    // Without a move count, assume the game lasts a while yet.
+    let moves_to_go = control.moves_to_go.unwrap_or(30).max(1);
+
+    let base = usable / moves_to_go + control.increment * 3 / 4;
+    let soft = base
+        .mul_f64(complexity(board, &legal_moves(board)))
+        .min(usable / 2);
+
+    Budget {
+        soft,
+        hard: (soft * 3).min(usable.mul_f64(0.8)),
+    }
+}
+
+/// A board scored with its pawn structure on top of `chess_engine`'s own evaluation.
+#[derive(Clone, Copy)]
+pub(crate) struct Structured(pub chess_engine::Board);
+
+impl Evaluate for Structured {
+    fn value_for(&self, color: chess_engine::Color) -> f64 {
+        self.0.value_for(color) + pawns::evaluate(&self.0, color)
+    }
+
+    fn get_current_player_color(&self) -> chess_engine::Color {
+        self.0.get_current_player_color()
+    }
+
+    fn get_legal_moves(&self) -> Vec<Move> {
+        self.0.get_legal_moves()
+    }
+
+    fn apply_eval_move(&self, m: Move) -> Self {
+        Self(self.0.apply_eval_move(m))
+    }
+}
+
+pub fn search(board: &chess_engine::Board, budget: Budget, max_depth: i32) -> (Move, u64, f64) {
+    let start = Instant::now();
+    let color = board.get_current_player_color();
+    let mut moves = legal_moves(board);
+    let mut board_count = 0;
+
+    match moves[..] {
+        [] => return (Move::Resign, 0, -999_999.0),
+        // A forced reply needs no thought.
+        [m] => return (m, 0, Structured(board.apply_eval_move(m)).value_for(color)),
+        _ => {}
+    }
+
+    // Try winning captures first and moves that hang a piece last, so the first depths,
+    // which are the ones most likely to be cut short, see the sharpest moves.
+    moves.sort_by_cached_key(|&m| Reverse(attacks::see(board, m)));
+
+    let mut best = (moves[0], f64::NEG_INFINITY);
+    let mut last_iteration = Duration::ZERO;
+
+    for depth in 0..=max_depth {
+        let iteration_start = Instant::now();
+        let mut iteration_best: Option<(Move, f64)> = None;
+
+        for &m in &moves {
+            // The shallowest search always finishes so there is a move to play, unless Ruby
+            // wants the thread back.
+            if gvl::interrupted() || (depth > 0 && start.elapsed() >= budget.hard) {
+                return (best.0, board_count, best.1);
+            }
+
+            let value = Structured(board.apply_eval_move(m)).minimax(
+                depth,
+                -1_000_000.0,
+                1_000_000.0,
+                false,
+                color,
+                &mut board_count,
+            );
+            if iteration_best.is_none_or(|(_, best)| value > best) {
+                iteration_best = Some((m, value));
+            }
+        }
+
+        if let Some(iteration_best) = iteration_best {
+            best = iteration_best;
+        }
+
+        // Search the best move first next time, so an interrupted depth still checks it.
+        if let Some(i) = moves.iter().position(|&m| m == best.0) {
+            moves[..=i].rotate_right(1);
+        }
+
+        // Each depth takes several times longer than the one before, so don't start one that
+        // can't finish in time.
+        let took = iteration_start.elapsed();
+        let growth = if last_iteration.is_zero() {
+            4.0
+        } else {
+            (took.as_secs_f64() / last_iteration.as_secs_f64()).clamp(2.0, 10.0)
+        };
+        last_iteration = took;
+
+        if start.elapsed() + took.mul_f64(growth) > budget.soft {
+            break;
+        }
+    }
+
+    (best.0, board_count, best.1)
+}
+
+fn root_search(
+    board: &chess_engine::Board,
+    depth: i32,
+    is_maximizing: bool,
+    getting_move_for: chess_engine::Color,
+) -> (Move, u64, f64) {
+    let mut best: Option<(Move, f64)> = None;
+    let mut board_count = 0;
+
+    // Try winning captures first, so they win ties with quieter moves.
+    let mut moves = legal_moves(board);
+    moves.sort_by_cached_key(|&m| Reverse(attacks::see(board, m)));
+
+    for m in moves {
+        if gvl::interrupted() {
+            break;
+        }
+
+        let value = Structured(board.apply_eval_move(m)).minimax(
+            depth,
+            -1_000_000.0,
+            1_000_000.0,
+            is_maximizing,
+            getting_move_for,
+            &mut board_count,
+        );
+        if best.is_none_or(|(_, best)| value > best) {
+            best = Some((m, value));
+        }
+    }
+
+    let (m, value) = best.unwrap_or((Move::Resign, -999_999.0));
+    (m, board_count, value)
+}
+
+pub fn best_at_depth(board: &chess_engine::Board, depth: i32) -> (Move, u64, f64) {
+    root_search(board, depth, false, board.get_current_player_color())
+}
+
+pub fn worst_at_depth(board: &chess_engine::Board, depth: i32) -> (Move, u64, f64) {
+    root_search(board, depth, true, !board.get_current_player_color())
+}
@@ -0,0 +1,216 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::fmt::Write;
+
+use chess_engine::{Color, Piece, Position};
+
+const LIGHT: &str = "#f0d9b5";
+const DARK: &str = "#b58863";
+const LAST_MOVE: &str = "#9bc700";
+const HIGHLIGHT: &str = "#d44a4a";
+const ARROW: &str = "#15781b";
+
+pub struct Options {
+    pub size: u32,
+    pub flipped: bool,
+    pub last_move: Option<(Position, Position)>,
+    pub arrows: Vec<(Position, Position)>,
+    pub highlights: Vec<Position>,
+    pub coordinates: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            size: 400,
+            flipped: false,
+            last_move: None,
+            arrows: Vec::new(),
+            highlights: Vec::new(),
+            coordinates: true,
+        }
+    }
+}
+
+fn is_dark(pos: Position) -> bool {
+    (pos.get_row() + pos.get_col()) % 2 == 0
+}
+
+/// The side of the box the piece outlines are drawn in.
+const PIECE_BOX: f64 = 45.0;
+
+/// Piece outlines, which are filled in each side's color so they don't depend on the fonts
+/// installed where the SVG is shown.
+const PIECES: [(&str, &str); 6] = [
+    (
+        "king",
+        "M11,37H34V33H11Z M13,33C10,26 12,19 18,18C20,17.5 22,19 22.5,21C23,19 25,17.5 27,18C33,19 35,26 32,33Z M21,5H24V8H27V11H24V17H21V11H18V8H21Z",
+    ),
+    (
+        "queen",
+        "M11,37H34V33H11Z M13,33L9,14L16,25L16.5,11L21,24L22.5,9L24,24L28.5,11L29,25L36,14L32,33Z M9,10A2.5,2.5 0 1,1 9,15A2.5,2.5 0 1,1 9,10Z M16.5,7A2.5,2.5 0 1,1 16.5,12A2.5,2.5 0 1,1 16.5,7Z M22.5,5A2.5,2.5 0 1,1 22.5,10A2.5,2.5 0 1,1 22.5,5Z M28.5,7A2.5,2.5 0 1,1 28.5,12A2.5,2.5 0 1,1 28.5,7Z M36,10A2.5,2.5 0 1,1 36,15A2.5,2.5 0 1,1 36,10Z",
+    ),
+    (
+        "rook",
+        "M11,37H34V33H11Z M14,33L15.5,17H29.5L31,33Z M12,17V9H16V12H20V9H25V12H29V9H33V17Z",
+    ),
+    (
+        "bishop",
+        "M11,37H34V33H11Z M16,33C14,26 17,19 22.5,13C28,19 31,26 29,33Z M22.5,7A3,3 0 1,1 22.5,13A3,3 0 1,1 22.5,7Z",
+    ),
+    (
+        "knight",
+        "M12,37H34V33H12Z M14,33C14,26 18,23 20,20C17,21 14,23 12,22C10,21 10,19 11,17C13,13 17,9 21,8L22,5L25,8C31,10 34,17 33,33Z",
+    ),
+    (
+        "pawn",
+        "M11,37H34V33H11Z M15,33C15,27 18,23 20.5,21.5H24.5C27,23 30,27 30,33Z M22.5,10A5.5,5.5 0 1,1 22.5,21A5.5,5.5 0 1,1 22.5,10Z",
+    ),
+];
+
+struct Layout {
+    square: f64,
+    flipped: bool,
+}
+
+impl Layout {
+    fn corner(&self, pos: Position) -> (f64, f64) {
+        let (col, row) = (f64::from(pos.get_col()), f64::from(pos.get_row()));
+        if self.flipped {
+            ((7.0 - col) * self.square, row * self.square)
+        } else {
+            (col * self.square, (7.0 - row) * self.square)
+        }
+    }
+
+    fn center(&self, pos: Position) -> (f64, f64) {
+        let (x, y) = self.corner(pos);
+        (x + self.square / 2.0, y + self.square / 2.0)
+    }
+
+    fn fill_square(&self, svg: &mut String, pos: Position, fill: &str, opacity: f64) {
+        let (x, y) = self.corner(pos);
+        let square = self.square;
+        write!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{square}" height="{square}" fill="{fill}" fill-opacity="{opacity}"/>"#
+        )
+        .unwrap();
+    }
+
+    fn coordinates(&self, svg: &mut String) {
+        let (square, font) = (self.square, self.square / 5.0);
+        // Labels take the color of the opposite square so they stay readable.
+        let label_fill = |pos: Position| if is_dark(pos) { LIGHT } else { DARK };
+        let (bottom_row, left_col) = if self.flipped { (7, 7) } else { (0, 0) };
+
+        for i in 0..8 {
+            let pos = Position::new(bottom_row, i);
+            let (x, y) = self.corner(pos);
+            let letter = char::from(b'a' + u8::try_from(i).unwrap());
+            write!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="{font}" font-family="sans-serif" text-anchor="end" fill="{}">{letter}</text>"#,
+                x + square - 2.0,
+                y + square - 2.0,
+                label_fill(pos)
+            )
+            .unwrap();
+
+            let pos = Position::new(i, left_col);
+            let (x, y) = self.corner(pos);
+            write!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="{font}" font-family="sans-serif" fill="{}">{}</text>"#,
+                x + 2.0,
+                y + font,
+                label_fill(pos),
+                i + 1
+            )
+            .unwrap();
+        }
+    }
+
+    fn piece(&self, svg: &mut String, pos: Position, piece: Piece) {
+        let (x, y) = self.corner(pos);
+        let fill = match piece.get_color() {
+            Color::White => "#fff",
+            Color::Black => "#000",
+        };
+        write!(
+            svg,
+            r##"<use xlink:href="#piece-{}" transform="translate({x},{y}) scale({})" fill="{fill}" stroke="#000" stroke-width="1.5" stroke-linejoin="round"/>"##,
+            piece.get_name(),
+            self.square / PIECE_BOX
+        )
+        .unwrap();
+    }
+
+    fn arrow(&self, svg: &mut String, from: Position, to: Position) {
+        let ((x1, y1), (x2, y2)) = (self.center(from), self.center(to));
+        // Stop short of the target's center so the arrowhead sits inside the square.
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let len = dx.hypot(dy).max(1.0);
+        let shorten = self.square * 0.3;
+        let (x2, y2) = (x2 - dx / len * shorten, y2 - dy / len * shorten);
+        write!(
+            svg,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{ARROW}" stroke-width="{}" stroke-opacity="0.8" stroke-linecap="round" marker-end="url(#arrowhead)"/>"#,
+            self.square / 6.0
+        )
+        .unwrap();
+    }
+}
+
+pub fn render(board: &chess_engine::Board, options: &Options) -> String {
+    let layout = Layout {
+        square: f64::from(options.size) / 8.0,
+        flipped: options.flipped,
+    };
+    let squares = || (0..8).flat_map(|row| (0..8).map(move |col| Position::new(row, col)));
+
+    let mut svg = String::new();
+    let size = options.size;
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#
+    )
+    .unwrap();
+    write!(
+        svg,
+        r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="3" markerHeight="3" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{ARROW}"/></marker>"#
+    )
+    .unwrap();
+    for (name, path) in PIECES {
+        write!(svg, r#"<path id="piece-{name}" d="{path}"/>"#).unwrap();
+    }
+    svg.push_str("</defs>");
+
+    for pos in squares() {
+        layout.fill_square(&mut svg, pos, if is_dark(pos) { DARK } else { LIGHT }, 1.0);
+    }
+    if let Some((from, to)) = options.last_move {
+        layout.fill_square(&mut svg, from, LAST_MOVE, 0.4);
+        layout.fill_square(&mut svg, to, LAST_MOVE, 0.4);
+    }
+    for &pos in &options.highlights {
+        layout.fill_square(&mut svg, pos, HIGHLIGHT, 0.5);
+    }
+    if options.coordinates {
+        layout.coordinates(&mut svg);
+    }
+    for pos in squares() {
+        if let Some(piece) = board.get_piece(pos) {
+            layout.piece(&mut svg, pos, piece);
+        }
+    }
+    for &(from, to) in &options.arrows {
+        layout.arrow(&mut svg, from, to);
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
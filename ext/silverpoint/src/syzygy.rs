@@ -0,0 +1,1397 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Probing Syzygy endgame tables.
+//!
+//! A table is read whole the first time it's needed and its header is checked then, so a
+//! damaged file fails the probe that opened it instead of being misread. Tables hold arbitrary
+//! values where a capture decides the game, so probes search captures before trusting them.
+
+// Squares, piece codes and file offsets all fit the integer types they're cast between, and
+// piece lists are too short to be worth counting any faster.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss,
+    clippy::naive_bytecount
+)]
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+use chess_engine::{Color, Evaluate, Move, Piece};
+
+use crate::{
+    board::{has_castling_right, is_mate, legal_moves},
+    gvl,
+    position::from_square_index,
+};
+
+const MAX_PIECES: usize = 7;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// The byte after the magic.
+const TABLE_SPLIT: u8 = 1;
+const TABLE_PAWNS: u8 = 2;
+
+// The byte starting each compressed part of a table.
+const PART_BLACK_TO_MOVE: u8 = 1;
+const PART_MAPPED: u8 = 2;
+const PART_WIN_PLIES: u8 = 4;
+const PART_LOSS_PLIES: u8 = 8;
+const PART_WIDE_MAP: u8 = 16;
+const PART_SINGLE_VALUE: u8 = 128;
+
+/// The right half of a symbol tree entry that marks it as a plain value.
+const LEAF: usize = 0xFFF;
+
+/// Piece letters in the order of their codes, which are 1 to 6 for white and 9 to 14 for black.
+const PIECE_LETTERS: [char; 6] = ['P', 'N', 'B', 'R', 'Q', 'K'];
+const PAWN: u8 = 1;
+const KING: u8 = 6;
+const BLACK: u8 = 8;
+
+/// Placements of the two kings, with the first in the a1-d1-d4 triangle.
+const KING_PLACEMENTS: u64 = 462;
+/// Placements of three unique pieces, with the first in the a1-d1-d4 triangle.
+const UNIQUE_PLACEMENTS: u64 = 31332;
+
+pub const WDL_LOSS: i32 = -2;
+pub const WDL_BLESSED_LOSS: i32 = -1;
+pub const WDL_DRAW: i32 = 0;
+pub const WDL_CURSED_WIN: i32 = 1;
+pub const WDL_WIN: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Wdl,
+    Dtz,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Little-endian reads that fail at the end of the table rather than reading past it.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn at(bytes: &'a [u8], at: usize) -> Self {
+        Self { bytes, at }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .at
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| invalid("the table is truncated"))?;
+        let bytes = &self.bytes[self.at..end];
+        self.at = end;
+
+        Ok(bytes)
+    }
+
+    /// Step over `count` items of `width` bytes, returning where they start.
+    fn skip(&mut self, count: usize, width: usize) -> io::Result<usize> {
+        let start = self.at;
+        let len = count
+            .checked_mul(width)
+            .ok_or_else(|| invalid("the table is truncated"))?;
+        // Alignment can leave the cursor past the end when nothing else follows.
+        if len > 0 {
+            self.take(len)?;
+        }
+
+        Ok(start)
+    }
+
+    fn align(&mut self, to: usize) {
+        self.at = self.at.next_multiple_of(to);
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// The pieces a table covers, from its name like `KRvK` with the first side as white.
+struct Material {
+    name: String,
+    codes: Vec<u8>,
+}
+
+impl Material {
+    fn parse(name: &str) -> Option<Self> {
+        let (white, black) = name.split_once('v')?;
+        let mut codes = Vec::new();
+        for (side, color) in [(white, 0), (black, BLACK)] {
+            for (i, letter) in side.chars().enumerate() {
+                let code = PIECE_LETTERS.iter().position(|&l| l == letter)? as u8 + 1;
+                // Each side has one king, named first.
+                if (code == KING) != (i == 0) {
+                    return None;
+                }
+                codes.push(code | color);
+            }
+        }
+
+        // Tables are only ever looked up by their canonical names.
+        let canonical = codes.len() <= MAX_PIECES && material_name(&codes, false) == name;
+        canonical.then(|| Self {
+            name: name.to_string(),
+            codes,
+        })
+    }
+
+    fn count(&self, code: u8) -> usize {
+        self.codes.iter().filter(|&&c| c == code).count()
+    }
+
+    fn symmetric(&self) -> bool {
+        self.name
+            .split_once('v')
+            .is_some_and(|(white, black)| white == black)
+    }
+
+    fn has_pawns(&self) -> bool {
+        self.count(PAWN) + self.count(PAWN | BLACK) > 0
+    }
+
+    fn has_pawns_on_both_sides(&self) -> bool {
+        self.count(PAWN) > 0 && self.count(PAWN | BLACK) > 0
+    }
+
+    fn has_unique_pieces(&self) -> bool {
+        self.codes
+            .iter()
+            .any(|&code| code & 7 != KING && self.count(code) == 1)
+    }
+}
+
+/// The name of the table for these pieces, like `KRvK`, with white or black first.
+fn material_name(codes: &[u8], black_first: bool) -> String {
+    let side = |color: u8| {
+        (1..=KING)
+            .rev()
+            .flat_map(|code| {
+                let count = codes.iter().filter(|&&c| c == code | color).count();
+                std::iter::repeat_n(PIECE_LETTERS[usize::from(code) - 1], count)
+            })
+            .collect::<String>()
+    };
+
+    let (first, second) = if black_first {
+        (side(BLACK), side(0))
+    } else {
+        (side(0), side(BLACK))
+    };
+    format!("{first}v{second}")
+}
+
+fn rank_of(sq: usize) -> usize {
+    sq >> 3
+}
+
+fn file_of(sq: usize) -> usize {
+    sq & 7
+}
+
+/// How far a square is above the a1-h8 diagonal, negative below it.
+fn diagonal_offset(sq: usize) -> i32 {
+    rank_of(sq) as i32 - file_of(sq) as i32
+}
+
+fn flip_diagonal(sq: usize) -> usize {
+    (sq >> 3) | ((sq & 7) << 3)
+}
+
+/// The square numberings indices are built from.
+struct Numbering {
+    /// Squares of the a1-d1-d4 triangle, those below the diagonal first.
+    triangle: [usize; 64],
+    /// Squares below the a1-h8 diagonal, in order.
+    below_diagonal: [usize; 64],
+    /// Placements of two kings, by the first king's triangle square and the second's square.
+    king_pairs: [[u64; 64]; 10],
+    binomial: [[u64; 64]; MAX_PIECES],
+    /// Pawn squares numbered from 47 down, the a- and h-files first, then b and g, and so on.
+    pawn_order: [usize; 64],
+    /// Where each square's placements of the leading pawns start, by how many lead.
+    lead_pawn_start: [[u64; 64]; 6],
+    /// Placements of the leading pawns with the first on each of the files a to d.
+    lead_pawn_placements: [[u64; 4]; 6],
+}
+
+fn numbering() -> &'static Numbering {
+    static NUMBERING: OnceLock<Numbering> = OnceLock::new();
+
+    NUMBERING.get_or_init(|| {
+        let below: Vec<usize> = (0..64).filter(|&sq| diagonal_offset(sq) < 0).collect();
+        let mut below_diagonal = [0; 64];
+        for (n, &sq) in below.iter().enumerate() {
+            below_diagonal[sq] = n;
+        }
+
+        let triangle_squares: Vec<usize> = below
+            .iter()
+            .copied()
+            .filter(|&sq| file_of(sq) <= 3)
+            .chain([0, 9, 18, 27])
+            .collect();
+        let mut triangle = [0; 64];
+        for (n, &sq) in triangle_squares.iter().enumerate() {
+            triangle[sq] = n;
+        }
+
+        // With the first king on the diagonal the second is kept on or below it, and pairs
+        // with both kings on the diagonal are numbered after all the others.
+        let mut king_pairs = [[0; 64]; 10];
+        let mut on_diagonal = Vec::new();
+        let mut next = 0;
+        for (n, &first) in triangle_squares.iter().enumerate() {
+            for (second, pair) in king_pairs[n].iter_mut().enumerate() {
+                let touching = rank_of(first).abs_diff(rank_of(second)) <= 1
+                    && file_of(first).abs_diff(file_of(second)) <= 1;
+                if touching || (diagonal_offset(first) == 0 && diagonal_offset(second) > 0) {
+                    continue;
+                }
+
+                if diagonal_offset(first) == 0 && diagonal_offset(second) == 0 {
+                    on_diagonal.push((n, second));
+                } else {
+                    *pair = next;
+                    next += 1;
+                }
+            }
+        }
+        for (n, second) in on_diagonal {
+            king_pairs[n][second] = next;
+            next += 1;
+        }
+
+        let mut binomial = [[0; 64]; MAX_PIECES];
+        for n in 0..64 {
+            binomial[0][n] = 1;
+            for k in 1..MAX_PIECES {
+                if n > 0 {
+                    binomial[k][n] = binomial[k - 1][n - 1] + binomial[k][n - 1];
+                }
+            }
+        }
+
+        let mut pawn_order = [0; 64];
+        let mut next = 48;
+        for file in 0..4 {
+            for rank in 1..7 {
+                let sq = rank * 8 + file;
+                pawn_order[sq] = next - 1;
+                pawn_order[sq ^ 7] = next - 2;
+                next -= 2;
+            }
+        }
+
+        let mut lead_pawn_start = [[0; 64]; 6];
+        let mut lead_pawn_placements = [[0; 4]; 6];
+        for count in 1..6 {
+            for (file, placements) in lead_pawn_placements[count].iter_mut().enumerate() {
+                let mut start = 0;
+                for rank in 1..7 {
+                    let sq = rank * 8 + file;
+                    lead_pawn_start[count][sq] = start;
+                    start += binomial[count - 1][pawn_order[sq]];
+                }
+                *placements = start;
+            }
+        }
+
+        Numbering {
+            triangle,
+            below_diagonal,
+            king_pairs,
+            binomial,
+            pawn_order,
+            lead_pawn_start,
+            lead_pawn_placements,
+        }
+    })
+}
+
+/// How one side to move of a table, for one leading pawn file, orders and numbers its pieces.
+struct Encoding {
+    pieces: Vec<u8>,
+    /// The groups pieces are placed in, as their length and their weight in the index.
+    groups: Vec<(usize, u64)>,
+    /// How many indices there are.
+    size: u64,
+}
+
+impl Encoding {
+    fn new(material: &Material, pieces: Vec<u8>, order: [u8; 2], file: usize) -> io::Result<Self> {
+        let mut found = pieces.clone();
+        let mut expected = material.codes.clone();
+        found.sort_unstable();
+        expected.sort_unstable();
+        if found != expected {
+            return Err(invalid("the header's pieces don't match the table's name"));
+        }
+
+        let has_pawns = material.has_pawns();
+        let unique = material.has_unique_pieces();
+        let both_pawns = material.has_pawns_on_both_sides();
+
+        // Without pawns, the first two or three pieces are placed together; otherwise the
+        // groups are runs of the same piece, starting with the leading pawns.
+        let together = match (has_pawns, unique) {
+            (true, _) => 1,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+        let mut lens: Vec<usize> = Vec::new();
+        for (i, &code) in pieces.iter().enumerate() {
+            match lens.last_mut() {
+                Some(len) if i < together || code == pieces[i - 1] => *len += 1,
+                _ => lens.push(1),
+            }
+        }
+
+        if has_pawns && pieces[0] & 7 != PAWN {
+            return Err(invalid("the table doesn't lead with a pawn"));
+        }
+        if both_pawns && (lens.len() < 2 || pieces[lens[0]] != pieces[0] ^ BLACK) {
+            return Err(invalid("the other side's pawns aren't placed second"));
+        }
+
+        let groups = lens.len();
+        let order = [usize::from(order[0]), usize::from(order[1])];
+        let order_valid =
+            order[0] < groups && (!both_pawns || (order[1] < groups && order[1] != order[0]));
+        if !order_valid {
+            return Err(invalid("the header's group order is out of range"));
+        }
+
+        // Each step of the order multiplies in one group's placements.
+        let numbering = numbering();
+        let mut weights = vec![0; groups];
+        let mut weight: u64 = 1;
+        let mut free = 64 - lens[0] - if both_pawns { lens[1] } else { 0 };
+        let mut next = if both_pawns { 2 } else { 1 };
+        for step in 0..groups {
+            let (group, placements) = if step == order[0] {
+                let placements = if has_pawns {
+                    numbering.lead_pawn_placements[lens[0]][file]
+                } else if unique {
+                    UNIQUE_PLACEMENTS
+                } else {
+                    KING_PLACEMENTS
+                };
+                (0, placements)
+            } else if both_pawns && step == order[1] {
+                (1, numbering.binomial[lens[1]][48 - lens[0]])
+            } else {
+                let placements = numbering.binomial[lens[next]][free];
+                free -= lens[next];
+                next += 1;
+                (next - 1, placements)
+            };
+
+            weights[group] = weight;
+            weight = weight
+                .checked_mul(placements)
+                .ok_or_else(|| invalid("the table is too large"))?;
+        }
+
+        Ok(Self {
+            pieces,
+            groups: lens.into_iter().zip(weights).collect(),
+            size: weight,
+        })
+    }
+}
+
+enum Node {
+    Value(u16),
+    Pair(usize, usize),
+}
+
+/// An entry of a symbol tree: 12 bits for the left half, then 12 for the right.
+fn node(tree: &[u8], sym: usize) -> Node {
+    let entry = &tree[3 * sym..3 * sym + 3];
+    let left = usize::from(entry[0]) | (usize::from(entry[1] & 0xF) << 8);
+    let right = usize::from(entry[1] >> 4) | (usize::from(entry[2]) << 4);
+
+    if right == LEAF {
+        Node::Value(left as u16)
+    } else {
+        Node::Pair(left, right)
+    }
+}
+
+/// How many values each symbol expands to, checking that the pairs form a tree.
+fn symbol_counts(tree: &[u8]) -> io::Result<Vec<u32>> {
+    let symbols = tree.len() / 3;
+    let mut counts = vec![0u32; symbols];
+    let mut expanding = vec![false; symbols];
+
+    for root in 0..symbols {
+        let mut stack = vec![root];
+        while let Some(&sym) = stack.last() {
+            if counts[sym] > 0 {
+                stack.pop();
+                continue;
+            }
+
+            let (left, right) = match node(tree, sym) {
+                Node::Value(_) => {
+                    counts[sym] = 1;
+                    stack.pop();
+                    continue;
+                }
+                Node::Pair(left, right) => (left, right),
+            };
+            if left >= symbols || right >= symbols {
+                return Err(invalid("a symbol pairs up symbols that don't exist"));
+            }
+
+            expanding[sym] = true;
+            if let Some(child) = [left, right].into_iter().find(|&child| counts[child] == 0) {
+                if expanding[child] {
+                    return Err(invalid("a symbol expands into itself"));
+                }
+                stack.push(child);
+                continue;
+            }
+
+            counts[sym] = counts[left]
+                .checked_add(counts[right])
+                .ok_or_else(|| invalid("a symbol expands into too many values"))?;
+            expanding[sym] = false;
+            stack.pop();
+        }
+    }
+
+    Ok(counts)
+}
+
+/// A block's bits, read from the top of a 64-bit window that's refilled 32 bits at a time.
+struct Bits<'a> {
+    bytes: &'a [u8],
+    next: usize,
+    window: u64,
+    available: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(bytes: &'a [u8], at: usize) -> Self {
+        let mut bits = Self {
+            bytes,
+            next: at,
+            window: 0,
+            available: 0,
+        };
+        bits.window = (u64::from(bits.refill()) << 32) | u64::from(bits.refill());
+        bits.available = 64;
+
+        bits
+    }
+
+    /// The next 32 bits. A block's last code can end within 32 bits of the end of the file,
+    /// so the window may reach past it, and the missing bits read as zero.
+    fn refill(&mut self) -> u32 {
+        let bytes = std::array::from_fn(|i| self.bytes.get(self.next + i).copied().unwrap_or(0));
+        self.next += 4;
+        u32::from_be_bytes(bytes)
+    }
+
+    fn consume(&mut self, len: u32) {
+        self.window <<= len;
+        self.available -= len;
+        if self.available <= 32 {
+            self.window |= u64::from(self.refill()) << (32 - self.available);
+            self.available += 32;
+        }
+    }
+}
+
+/// One side to move (and leading pawn file) of a table: a sequence of values, Huffman coded
+/// into blocks.
+#[derive(Default)]
+struct Part {
+    flags: u8,
+    /// The only value, for parts that store no blocks.
+    single: Option<u8>,
+    block_size: usize,
+    /// How many values each sparse index entry covers.
+    span: u64,
+    blocks: usize,
+    /// Entries in the block length list, which may run past the last block.
+    block_lengths: usize,
+    min_len: u32,
+    /// The lowest code of each length from the shortest, aligned to the top of 64 bits.
+    bases: Vec<u64>,
+    /// The symbol of the lowest code of each length.
+    firsts: Vec<u16>,
+    tree: usize,
+    /// How many values each symbol expands to.
+    counts: Vec<u32>,
+    /// DTZ values for a win, loss, cursed win and blessed loss, each as a start and length.
+    maps: [(usize, usize); 4],
+    sparse_index_at: usize,
+    sparse_index_len: usize,
+    block_lengths_at: usize,
+    data_at: usize,
+}
+
+impl Part {
+    fn read(r: &mut Reader<'_>, size: u64) -> io::Result<Self> {
+        let flags = r.u8()?;
+        if flags & PART_SINGLE_VALUE != 0 {
+            return Ok(Self {
+                flags,
+                single: Some(r.u8()?),
+                ..Self::default()
+            });
+        }
+
+        let (block_bits, span_bits) = (r.u8()?, r.u8()?);
+        if block_bits > 31 || span_bits > 31 {
+            return Err(invalid(format!(
+                "blocks of 2^{block_bits} bytes with 2^{span_bits} values per index entry"
+            )));
+        }
+        let padding = usize::from(r.u8()?);
+        let blocks = r.u32()? as usize;
+
+        let (max_len, min_len) = (r.u8()?, r.u8()?);
+        if min_len == 0 || min_len > max_len || max_len > 32 {
+            return Err(invalid(format!("codes of {min_len} to {max_len} bits")));
+        }
+        let lengths = usize::from(max_len - min_len) + 1;
+        let firsts = (0..lengths)
+            .map(|_| r.u16())
+            .collect::<io::Result<Vec<_>>>()?;
+        if firsts.windows(2).any(|pair| pair[0] < pair[1]) {
+            return Err(invalid("longer codes don't have the lower symbols"));
+        }
+
+        // Longer codes come first, so each length's lowest code follows from the next longer
+        // length's lowest code and how many codes that length has.
+        let mut bases = vec![0u64; lengths];
+        for i in (0..lengths - 1).rev() {
+            let longer = u64::from(firsts[i] - firsts[i + 1]);
+            bases[i] = (bases[i + 1] + longer) >> 1;
+        }
+        for (len, base) in (u32::from(min_len)..).zip(&mut bases) {
+            *base <<= 64 - len;
+        }
+
+        let symbols = usize::from(r.u16()?);
+        if symbols > LEAF {
+            return Err(invalid(format!("{symbols} symbols")));
+        }
+        let tree = r.skip(symbols, 3)?;
+        r.skip(symbols % 2, 1)?;
+        let counts = symbol_counts(&r.bytes[tree..tree + 3 * symbols])?;
+
+        let span = 1 << span_bits;
+        let sparse_index_len =
+            usize::try_from(size.div_ceil(span)).map_err(|_| invalid("the table is too large"))?;
+
+        Ok(Self {
+            flags,
+            block_size: 1 << block_bits,
+            span,
+            blocks,
+            block_lengths: blocks + padding,
+            min_len: u32::from(min_len),
+            bases,
+            firsts,
+            tree,
+            counts,
+            sparse_index_len,
+            ..Self::default()
+        })
+    }
+
+    fn read_maps(&mut self, r: &mut Reader<'_>) -> io::Result<()> {
+        if self.flags & PART_MAPPED == 0 {
+            return Ok(());
+        }
+
+        let width = if self.flags & PART_WIDE_MAP == 0 {
+            1
+        } else {
+            r.align(2);
+            2
+        };
+        for map in &mut self.maps {
+            let len = if width == 1 {
+                usize::from(r.u8()?)
+            } else {
+                usize::from(r.u16()?)
+            };
+            *map = (r.skip(len, width)?, len);
+        }
+
+        Ok(())
+    }
+
+    fn read_sections(&mut self, r: &mut Reader<'_>, section: usize) -> io::Result<()> {
+        match section {
+            0 => self.sparse_index_at = r.skip(self.sparse_index_len, 6)?,
+            1 => self.block_lengths_at = r.skip(self.block_lengths, 2)?,
+            _ => {
+                r.align(64);
+                self.data_at = r.skip(self.blocks, self.block_size)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The values in a block.
+    fn block_len(&self, bytes: &[u8], block: usize) -> io::Result<i64> {
+        if block >= self.block_lengths {
+            return Err(invalid("a value falls outside the table's blocks"));
+        }
+
+        let len = Reader::at(bytes, self.block_lengths_at + 2 * block).u16()?;
+        Ok(i64::from(len) + 1)
+    }
+
+    fn value(&self, bytes: &[u8], idx: u64) -> io::Result<u16> {
+        if let Some(value) = self.single {
+            return Ok(u16::from(value));
+        }
+
+        let entry = usize::try_from(idx / self.span)
+            .ok()
+            .filter(|&entry| entry < self.sparse_index_len)
+            .ok_or_else(|| invalid("an index is past the end of the table"))?;
+
+        // Each sparse index entry gives the block and offset of the middle value of its span.
+        let mut r = Reader::at(bytes, self.sparse_index_at + 6 * entry);
+        let mut block = r.u32()? as usize;
+        let mut offset = i64::from(r.u16()?) + (idx % self.span) as i64 - (self.span / 2) as i64;
+        while offset < 0 {
+            block = block
+                .checked_sub(1)
+                .ok_or_else(|| invalid("a value falls before the table's first block"))?;
+            offset += self.block_len(bytes, block)?;
+        }
+        loop {
+            let len = self.block_len(bytes, block)?;
+            if offset < len {
+                break;
+            }
+            offset -= len;
+            block += 1;
+        }
+        if block >= self.blocks {
+            return Err(invalid("a value falls outside the table's blocks"));
+        }
+
+        let tree = &bytes[self.tree..self.tree + 3 * self.counts.len()];
+        let mut bits = Bits::new(bytes, self.data_at + block * self.block_size);
+        let mut sym = loop {
+            let mut len = 0;
+            while bits.window < self.bases[len] {
+                len += 1;
+            }
+            let code_len = self.min_len + len as u32;
+
+            let sym =
+                ((bits.window - self.bases[len]) >> (64 - code_len)) + u64::from(self.firsts[len]);
+            let sym = usize::try_from(sym)
+                .ok()
+                .filter(|&sym| sym < self.counts.len())
+                .ok_or_else(|| invalid("a code decodes to a symbol that doesn't exist"))?;
+
+            let count = i64::from(self.counts[sym]);
+            if offset < count {
+                break sym;
+            }
+            offset -= count;
+            bits.consume(code_len);
+        };
+
+        loop {
+            match node(tree, sym) {
+                Node::Value(value) => return Ok(value),
+                Node::Pair(left, right) => {
+                    let count = i64::from(self.counts[left]);
+                    if offset < count {
+                        sym = left;
+                    } else {
+                        offset -= count;
+                        sym = right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn mapped(&self, bytes: &[u8], wdl: i32, value: u16) -> io::Result<i32> {
+        let map = match wdl {
+            WDL_LOSS => 1,
+            WDL_CURSED_WIN => 2,
+            WDL_BLESSED_LOSS => 3,
+            _ => 0,
+        };
+        let (start, len) = self.maps[map];
+        let value = usize::from(value);
+        if value >= len {
+            return Err(invalid("a DTZ value is missing from its map"));
+        }
+
+        let mut r = Reader::at(bytes, start);
+        if self.flags & PART_WIDE_MAP == 0 {
+            r.at += value;
+            r.u8().map(i32::from)
+        } else {
+            r.at += 2 * value;
+            r.u16().map(i32::from)
+        }
+    }
+}
+
+/// What a DTZ table holds for a position.
+enum Stored {
+    Dtz(i32),
+    /// The table only holds the other side to move.
+    OtherSide,
+}
+
+struct Table {
+    kind: Kind,
+    bytes: Vec<u8>,
+    material: Material,
+    /// An entry for each file the leading pawn can be on, or one for tables without pawns,
+    /// with the encoding and values of each side to move the table holds.
+    files: Vec<Vec<(Encoding, Part)>>,
+}
+
+impl Table {
+    fn open(path: &Path, kind: Kind, name: &str) -> io::Result<Self> {
+        let material = Material::parse(name).ok_or_else(|| invalid("not a table name"))?;
+        let bytes = fs::read(path)?;
+        let files = Self::read(&bytes, kind, &material)?;
+
+        Ok(Self {
+            kind,
+            bytes,
+            material,
+            files,
+        })
+    }
+
+    fn read(
+        bytes: &[u8],
+        kind: Kind,
+        material: &Material,
+    ) -> io::Result<Vec<Vec<(Encoding, Part)>>> {
+        let mut r = Reader::at(bytes, 0);
+        let magic = match kind {
+            Kind::Wdl => WDL_MAGIC,
+            Kind::Dtz => DTZ_MAGIC,
+        };
+        if r.take(4).ok() != Some(&magic[..]) {
+            return Err(invalid("not a Syzygy table"));
+        }
+
+        let flags = r.u8()?;
+        if (flags & TABLE_PAWNS != 0) != material.has_pawns() {
+            return Err(invalid(
+                "the header disagrees with the table's name about pawns",
+            ));
+        }
+        // Only WDL tables hold both sides to move, and only when the material differs.
+        let sides = match kind {
+            Kind::Wdl if (flags & TABLE_SPLIT != 0) == material.symmetric() => {
+                return Err(invalid(
+                    "the header disagrees with the table's name about symmetry",
+                ));
+            }
+            Kind::Wdl if flags & TABLE_SPLIT != 0 => 2,
+            _ => 1,
+        };
+
+        let file_count = if material.has_pawns() { 4 } else { 1 };
+        let mut encodings = Vec::new();
+        for file in 0..file_count {
+            let order = r.u8()?;
+            let pawn_order = if material.has_pawns_on_both_sides() {
+                r.u8()?
+            } else {
+                0xFF
+            };
+            let pieces = r.take(material.codes.len())?;
+
+            // Each byte holds a piece for the first side to move in its low half, and the
+            // second side in its high half.
+            let side_encodings = (0..sides)
+                .map(|side| {
+                    let half = |byte: u8| byte >> (4 * side) & 0xF;
+                    let pieces = pieces.iter().map(|&byte| half(byte)).collect();
+                    Encoding::new(material, pieces, [half(order), half(pawn_order)], file)
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            encodings.push(side_encodings);
+        }
+        r.align(2);
+
+        let mut files = Vec::new();
+        for file in encodings {
+            let mut sides = Vec::new();
+            for encoding in file {
+                let part = Part::read(&mut r, encoding.size)?;
+                sides.push((encoding, part));
+            }
+            files.push(sides);
+        }
+
+        if kind == Kind::Dtz {
+            for file in &mut files {
+                file[0].1.read_maps(&mut r)?;
+            }
+            r.align(2);
+        }
+
+        // Then come each part's sparse index, each part's block lengths and each part's data.
+        for section in 0..3 {
+            for (_, part) in files.iter_mut().flatten() {
+                if part.single.is_none() {
+                    part.read_sections(&mut r, section)?;
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Where the table keeps a position: the leading pawn's file, the side to move as the
+    /// table has it, and the index.
+    fn locate(&self, pieces: &[(usize, u8)], black_to_move: bool) -> (usize, usize, u64) {
+        let numbering = numbering();
+
+        // The table has its first named side as white, and symmetric tables are only stored
+        // with white to move.
+        let codes: Vec<u8> = pieces.iter().map(|&(_, code)| code).collect();
+        let flip = (self.material.symmetric() && black_to_move)
+            || material_name(&codes, false) != self.material.name;
+        let side = usize::from(flip != black_to_move);
+        let (color, rank) = if flip { (BLACK, 0o70) } else { (0, 0) };
+        let mut placed: Vec<(usize, u8)> = pieces
+            .iter()
+            .map(|&(sq, code)| (sq ^ rank, code ^ color))
+            .collect();
+
+        // The leading pawns go first, and the one furthest along the pawn order picks the file.
+        let mut lead = 0;
+        let mut file = 0;
+        if self.material.has_pawns() {
+            let pawn = self.files[0][0].0.pieces[0];
+            placed.sort_by_key(|&(sq, code)| (code != pawn, Reverse(numbering.pawn_order[sq])));
+            lead = placed.iter().filter(|&&(_, code)| code == pawn).count();
+            file = file_of(placed[0].0).min(7 - file_of(placed[0].0));
+        }
+
+        let (encoding, _) = match self.kind {
+            Kind::Wdl => &self.files[file][side],
+            Kind::Dtz => &self.files[file][0],
+        };
+        for i in lead..placed.len() {
+            if let Some(j) = (i..placed.len()).find(|&j| placed[j].1 == encoding.pieces[i]) {
+                placed.swap(i, j);
+            }
+        }
+
+        let mut squares: Vec<usize> = placed.iter().map(|&(sq, _)| sq).collect();
+        if file_of(squares[0]) > 3 {
+            for sq in &mut squares {
+                *sq ^= 7;
+            }
+        }
+
+        let mut idx = if self.material.has_pawns() {
+            squares[1..lead].sort_unstable_by_key(|&sq| numbering.pawn_order[sq]);
+            numbering.lead_pawn_start[lead][squares[0]]
+                + (1..lead)
+                    .map(|i| numbering.binomial[i][numbering.pawn_order[squares[i]]])
+                    .sum::<u64>()
+        } else {
+            if rank_of(squares[0]) > 3 {
+                for sq in &mut squares {
+                    *sq ^= 0o70;
+                }
+            }
+            // Reflect in the a1-h8 diagonal if the first leading piece off it is above it.
+            let leading = encoding.groups[0].0;
+            if let Some(&sq) = squares[..leading]
+                .iter()
+                .find(|&&sq| diagonal_offset(sq) != 0)
+            {
+                if diagonal_offset(sq) > 0 {
+                    for sq in &mut squares {
+                        *sq = flip_diagonal(*sq);
+                    }
+                }
+            }
+
+            if self.material.has_unique_pieces() {
+                unique_index(numbering, [squares[0], squares[1], squares[2]])
+            } else {
+                numbering.king_pairs[numbering.triangle[squares[0]]][squares[1]]
+            }
+        };
+        idx *= encoding.groups[0].1;
+
+        // Every other group is numbered by its squares, skipping those already taken.
+        let mut start = encoding.groups[0].0;
+        for (group, &(len, weight)) in encoding.groups.iter().enumerate().skip(1) {
+            let (taken, rest) = squares.split_at_mut(start);
+            let members = &mut rest[..len];
+            members.sort_unstable();
+
+            // The other side's pawns can't be on the first rank either.
+            let offset = if group == 1 && self.material.has_pawns_on_both_sides() {
+                8
+            } else {
+                0
+            };
+            let placement: u64 = members
+                .iter()
+                .enumerate()
+                .map(|(i, &sq)| {
+                    let below = taken.iter().filter(|&&s| s < sq).count();
+                    numbering.binomial[i + 1][sq - below - offset]
+                })
+                .sum();
+
+            idx += placement * weight;
+            start += len;
+        }
+
+        (file, side, idx)
+    }
+
+    fn wdl(&self, pieces: &[(usize, u8)], black_to_move: bool) -> io::Result<i32> {
+        let (file, side, idx) = self.locate(pieces, black_to_move);
+        let value = self.files[file][side].1.value(&self.bytes, idx)?;
+        if value > 4 {
+            return Err(invalid(format!("{value} is not a WDL value")));
+        }
+
+        Ok(i32::from(value) - 2)
+    }
+
+    /// The distance to zeroing in plies, without the sign or the extra 100 plies of a cursed
+    /// result.
+    fn dtz(&self, pieces: &[(usize, u8)], black_to_move: bool, wdl: i32) -> io::Result<Stored> {
+        let (file, side, idx) = self.locate(pieces, black_to_move);
+        let part = &self.files[file][0].1;
+        let stored_side = usize::from(part.flags & PART_BLACK_TO_MOVE);
+        if stored_side != side && (self.material.has_pawns() || !self.material.symmetric()) {
+            return Ok(Stored::OtherSide);
+        }
+
+        let value = part.value(&self.bytes, idx)?;
+        let dtz = if part.flags & PART_MAPPED == 0 {
+            i32::from(value)
+        } else {
+            part.mapped(&self.bytes, wdl, value)?
+        };
+
+        // Values are counted in moves unless the table says they're in plies.
+        let in_moves = match wdl {
+            WDL_WIN => part.flags & PART_WIN_PLIES == 0,
+            WDL_LOSS => part.flags & PART_LOSS_PLIES == 0,
+            _ => true,
+        };
+        Ok(Stored::Dtz(if in_moves { 2 * dtz + 1 } else { dtz + 1 }))
+    }
+}
+
+/// Number three unique pieces, the first in the a1-d1-d4 triangle and all on or below the
+/// diagonal while the ones before them are on it.
+fn unique_index(numbering: &Numbering, [s0, s1, s2]: [usize; 3]) -> u64 {
+    // Later pieces skip the squares of the ones before them.
+    let before1 = usize::from(s1 > s0);
+    let before2 = usize::from(s2 > s0) + usize::from(s2 > s1);
+    let rank0 = rank_of(s0);
+
+    let idx = if diagonal_offset(s0) != 0 {
+        (numbering.triangle[s0] * 63 + s1 - before1) * 62 + s2 - before2
+    } else if diagonal_offset(s1) != 0 {
+        (6 * 63 + rank0 * 28 + numbering.below_diagonal[s1]) * 62 + s2 - before2
+    } else if diagonal_offset(s2) != 0 {
+        // The first two are on the diagonal, so only their ranks count.
+        let rank1 = rank_of(s1) - before1;
+        6 * 63 * 62 + 4 * 28 * 62 + (rank0 * 7 + rank1) * 28 + numbering.below_diagonal[s2]
+    } else {
+        let rank1 = rank_of(s1) - before1;
+        let rank2 = rank_of(s2) - before2;
+        6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + (rank0 * 7 + rank1) * 6 + rank2
+    };
+
+    idx as u64
+}
+
+fn piece_code(piece: Piece) -> u8 {
+    let code = match piece {
+        Piece::Pawn(..) => PAWN,
+        Piece::Knight(..) => 2,
+        Piece::Bishop(..) => 3,
+        Piece::Rook(..) => 4,
+        Piece::Queen(..) => 5,
+        Piece::King(..) => KING,
+    };
+
+    match piece.get_color() {
+        Color::White => code,
+        Color::Black => code | BLACK,
+    }
+}
+
+fn board_pieces(board: &chess_engine::Board) -> Vec<(usize, u8)> {
+    (0..64)
+        .filter_map(|sq| {
+            board
+                .get_piece(from_square_index(sq))
+                .map(|piece| (sq, piece_code(piece)))
+        })
+        .collect()
+}
+
+fn is_capture(board: &chess_engine::Board, m: Move) -> bool {
+    match m {
+        Move::Piece(from, to) => {
+            board.has_enemy_piece(to, board.get_turn_color())
+                || (matches!(board.get_piece(from), Some(Piece::Pawn(..)))
+                    && board.get_en_passant() == Some(to))
+        }
+        _ => false,
+    }
+}
+
+fn is_zeroing(board: &chess_engine::Board, m: Move) -> bool {
+    match m {
+        Move::Piece(from, _) => {
+            is_capture(board, m) || matches!(board.get_piece(from), Some(Piece::Pawn(..)))
+        }
+        _ => false,
+    }
+}
+
+/// The DTZ of a position whose best move is zeroing and leads to `wdl`.
+fn zeroing_dtz(wdl: i32) -> i32 {
+    match wdl {
+        WDL_WIN => 1,
+        WDL_CURSED_WIN => 101,
+        WDL_BLESSED_LOSS => -101,
+        WDL_LOSS => -1,
+        _ => 0,
+    }
+}
+
+type Key = (String, Kind);
+
+/// A directory of Syzygy tables, loaded into memory as they are first probed.
+pub struct Tablebases {
+    files: HashMap<Key, PathBuf>,
+    tables: Mutex<HashMap<Key, Arc<Table>>>,
+    max_pieces: usize,
+}
+
+impl Tablebases {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        let mut files = HashMap::new();
+        let mut max_pieces = 0;
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let kind = match path.extension().and_then(|s| s.to_str()) {
+                Some("rtbw") => Kind::Wdl,
+                Some("rtbz") => Kind::Dtz,
+                _ => continue,
+            };
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Some(material) = Material::parse(name) else {
+                continue;
+            };
+
+            if kind == Kind::Wdl {
+                max_pieces = max_pieces.max(material.codes.len());
+            }
+            files.insert((name.to_string(), kind), path);
+        }
+
+        Ok(Self {
+            files,
+            tables: Mutex::new(HashMap::new()),
+            max_pieces,
+        })
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    fn table(&self, name: &str, kind: Kind) -> io::Result<Option<Arc<Table>>> {
+        let key = (name.to_string(), kind);
+        let mut tables = self.tables.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(table) = tables.get(&key) {
+            return Ok(Some(table.clone()));
+        }
+        let Some(path) = self.files.get(&key) else {
+            return Ok(None);
+        };
+
+        let table = Table::open(path, kind, name)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+        let table = Arc::new(table);
+        tables.insert(key, table.clone());
+
+        Ok(Some(table))
+    }
+
+    fn find(&self, pieces: &[(usize, u8)], kind: Kind) -> io::Result<Option<Arc<Table>>> {
+        let codes: Vec<u8> = pieces.iter().map(|&(_, code)| code).collect();
+        for black_first in [false, true] {
+            if let Some(table) = self.table(&material_name(&codes, black_first), kind)? {
+                return Ok(Some(table));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The WDL the table holds for the side to move, which may be wrong where a capture wins.
+    fn stored_wdl(&self, board: &chess_engine::Board) -> io::Result<Option<i32>> {
+        // Giving up is as good as any answer, since an interrupted probe is thrown away.
+        if gvl::interrupted() {
+            return Ok(None);
+        }
+
+        let pieces = board_pieces(board);
+        if pieces.len() == 2 {
+            return Ok(Some(WDL_DRAW));
+        }
+        let Some(table) = self.find(&pieces, Kind::Wdl)? else {
+            return Ok(None);
+        };
+
+        table
+            .wdl(&pieces, board.get_turn_color() == Color::Black)
+            .map(Some)
+    }
+
+    fn stored_dtz(&self, board: &chess_engine::Board, wdl: i32) -> io::Result<Option<Stored>> {
+        if gvl::interrupted() {
+            return Ok(None);
+        }
+
+        let pieces = board_pieces(board);
+        let Some(table) = self.find(&pieces, Kind::Dtz)? else {
+            return Ok(None);
+        };
+
+        table
+            .dtz(&pieces, board.get_turn_color() == Color::Black, wdl)
+            .map(Some)
+    }
+
+    /// The WDL for the side to move, trying captures (and pawn moves too, if `zeroing` is set)
+    /// before the table. Also says whether one of those moves is the best.
+    fn resolve(
+        &self,
+        board: &chess_engine::Board,
+        zeroing: bool,
+    ) -> io::Result<Option<(i32, bool)>> {
+        let moves = legal_moves(board);
+        let mut best = WDL_LOSS;
+        let mut tried = 0;
+
+        for &m in &moves {
+            let counts = if zeroing {
+                is_zeroing(board, m)
+            } else {
+                is_capture(board, m)
+            };
+            if !counts {
+                continue;
+            }
+            tried += 1;
+
+            let Some((wdl, _)) = self.resolve(&board.apply_eval_move(m), false)? else {
+                return Ok(None);
+            };
+            best = best.max(-wdl);
+            if best == WDL_WIN {
+                return Ok(Some((best, true)));
+            }
+        }
+
+        // With every move tried the table isn't needed, and may even be wrong, as it doesn't
+        // know about en passant.
+        let tried_all = tried > 0 && tried == moves.len();
+        let stored = if tried_all {
+            best
+        } else {
+            let Some(stored) = self.stored_wdl(board)? else {
+                return Ok(None);
+            };
+            stored
+        };
+
+        Ok(Some(if best >= stored {
+            (best, best > WDL_DRAW || tried_all)
+        } else {
+            (stored, false)
+        }))
+    }
+
+    fn can_probe(&self, board: &chess_engine::Board) -> bool {
+        let pieces = board_pieces(board);
+        let castling = [Color::White, Color::Black].iter().any(|&color| {
+            has_castling_right(board, color, true) || has_castling_right(board, color, false)
+        });
+        // Pawn placements only cover the second to seventh ranks.
+        let stray_pawn = pieces
+            .iter()
+            .any(|&(sq, code)| code & 7 == PAWN && !(8..56).contains(&sq));
+
+        pieces.len() <= self.max_pieces && !castling && !stray_pawn
+    }
+
+    /// Win/draw/loss for the side to move, from -2 (loss) to 2 (win).
+    pub fn probe_wdl(&self, board: &chess_engine::Board) -> io::Result<Option<i32>> {
+        if !self.can_probe(board) {
+            return Ok(None);
+        }
+
+        Ok(self.resolve(board, false)?.map(|(wdl, _)| wdl))
+    }
+
+    /// Distance to the next zeroing move in plies, signed by the result for the side to move.
+    pub fn probe_dtz(&self, board: &chess_engine::Board) -> io::Result<Option<i32>> {
+        if !self.can_probe(board) {
+            return Ok(None);
+        }
+
+        self.dtz(board)
+    }
+
+    fn dtz(&self, board: &chess_engine::Board) -> io::Result<Option<i32>> {
+        let Some((wdl, zeroing_best)) = self.resolve(board, true)? else {
+            return Ok(None);
+        };
+        if wdl == WDL_DRAW {
+            return Ok(Some(0));
+        }
+        if zeroing_best {
+            return Ok(Some(zeroing_dtz(wdl)));
+        }
+
+        match self.stored_dtz(board, wdl)? {
+            None => return Ok(None),
+            Some(Stored::Dtz(dtz)) => {
+                let cursed = wdl == WDL_BLESSED_LOSS || wdl == WDL_CURSED_WIN;
+                return Ok(Some((dtz + if cursed { 100 } else { 0 }) * wdl.signum()));
+            }
+            Some(Stored::OtherSide) => {}
+        }
+
+        // Look one move ahead to a position the table holds.
+        let mut best = i32::MAX;
+        for m in legal_moves(board) {
+            let zeroing = is_zeroing(board, m);
+            let next = board.apply_eval_move(m);
+
+            let mut dtz = if zeroing {
+                let Some((wdl, _)) = self.resolve(&next, false)? else {
+                    return Ok(None);
+                };
+                -zeroing_dtz(wdl)
+            } else {
+                let Some(dtz) = self.dtz(&next)? else {
+                    return Ok(None);
+                };
+                -dtz
+            };
+
+            if dtz == 1 && is_mate(&next) {
+                best = 1;
+            }
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+            if dtz < best && dtz.signum() == wdl.signum() {
+                best = dtz;
+            }
+        }
+
+        Ok(Some(if best == i32::MAX { -1 } else { best }))
+    }
+
+    /// The move that keeps the best result while making the quickest progress.
+    pub fn best_move(&self, board: &chess_engine::Board) -> io::Result<Option<Move>> {
+        if !self.can_probe(board) {
+            return Ok(None);
+        }
+
+        let mut best: Option<((i32, i32), Move)> = None;
+        for m in legal_moves(board) {
+            let next = board.apply_eval_move(m);
+            if is_mate(&next) {
+                return Ok(Some(m));
+            }
+
+            let dtz = if is_zeroing(board, m) {
+                let Some((wdl, _)) = self.resolve(&next, false)? else {
+                    return Ok(None);
+                };
+                zeroing_dtz(-wdl)
+            } else {
+                let Some(dtz) = self.dtz(&next)? else {
+                    return Ok(None);
+                };
+                -dtz - dtz.signum()
+            };
+
+            // Win as quickly as possible, and lose as slowly as possible.
+            let rank = match dtz.signum() {
+                1 => (2, -dtz),
+                0 => (1, 0),
+                _ => (0, -dtz),
+            };
+            if best.is_none_or(|(best_rank, _)| rank > best_rank) {
+                best = Some((rank, m));
+            }
+        }
+
+        Ok(best.map(|(_, m)| m))
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use magnus::{function, method, Module, Object};
+
+use crate::{board::Board, enums::Move, gvl::no_gvl, syzygy};
+
+#[magnus::wrap(class = "Silverpoint::Tablebase", size, free_immediately)]
+pub struct Tablebase(syzygy::Tablebases);
+
+/// A table that can't be read, or turns out to be damaged once it is.
+fn table_error(err: &std::io::Error) -> magnus::Error {
+    let ruby = magnus::Ruby::get().unwrap();
+    magnus::Error::new(ruby.exception_io_error(), err.to_string())
+}
+
+impl Tablebase {
+    fn new(dir: String) -> Result<Self, magnus::Error> {
+        let ruby = magnus::Ruby::get().unwrap();
+        let tables = syzygy::Tablebases::open(dir.as_ref()).map_err(|err| {
+            magnus::Error::new(ruby.exception_io_error(), format!("{dir}: {err}"))
+        })?;
+
+        Ok(Self(tables))
+    }
+
+    /// The move to play from a tablebase position, if the tables cover it.
+    pub fn best_move_for(
+        &self,
+        board: &chess_engine::Board,
+    ) -> Result<Option<chess_engine::Move>, magnus::Error> {
+        no_gvl!(self.0.best_move(board))?.map_err(|err| table_error(&err))
+    }
+
+    fn max_pieces(&self) -> usize {
+        self.0.max_pieces()
+    }
+
+    fn probe_wdl(&self, board: &Board) -> Result<Option<i32>, magnus::Error> {
+        let board = (*board).into();
+        no_gvl!(self.0.probe_wdl(&board))?.map_err(|err| table_error(&err))
+    }
+
+    fn probe_dtz(&self, board: &Board) -> Result<Option<i32>, magnus::Error> {
+        let board = (*board).into();
+        no_gvl!(self.0.probe_dtz(&board))?.map_err(|err| table_error(&err))
+    }
+
+    fn best_move(&self, board: &Board) -> Result<Option<Move>, magnus::Error> {
+        Ok(self.best_move_for(&(*board).into())?.map(Into::into))
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let class = module.define_class("Tablebase", ruby.class_object())?;
+    class.define_singleton_method("new", function!(Tablebase::new, 1))?;
+
+    class.define_method("max_pieces", method!(Tablebase::max_pieces, 0))?;
+    class.define_method("probe_wdl", method!(Tablebase::probe_wdl, 1))?;
+    class.define_method("probe_dtz", method!(Tablebase::probe_dtz, 1))?;
+    class.define_method("best_move", method!(Tablebase::best_move, 1))?;
+
+    class.const_set("LOSS", syzygy::WDL_LOSS)?;
+    class.const_set("BLESSED_LOSS", syzygy::WDL_BLESSED_LOSS)?;
+    class.const_set("DRAW", syzygy::WDL_DRAW)?;
+    class.const_set("CURSED_WIN", syzygy::WDL_CURSED_WIN)?;
+    class.const_set("WIN", syzygy::WDL_WIN)?;
+
+    Ok(())
+}
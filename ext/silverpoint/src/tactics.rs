@@ -0,0 +1,334 @@
+// Copyright (c) 2023 Lily Lyons
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use chess_engine::{Color, Evaluate, Piece, Position};
+use magnus::{function, method, Module, Object, Symbol};
+
+use crate::{
+    attacks,
+    board::{legal_moves, Board},
+    enums::{self, Move},
+    errors,
+    frozen::frozen_wrap,
+    geometry::{self, Direction},
+    gvl::no_gvl,
+    position::{from_square_index, square_index},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Fork,
+    Pin,
+    Skewer,
+    DiscoveredAttack,
+    DoubleCheck,
+    HangingPiece,
+    BackRank,
+    RemovalOfDefender,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Fork => "fork",
+            Self::Pin => "pin",
+            Self::Skewer => "skewer",
+            Self::DiscoveredAttack => "discovered_attack",
+            Self::DoubleCheck => "double_check",
+            Self::HangingPiece => "hanging_piece",
+            Self::BackRank => "back_rank",
+            Self::RemovalOfDefender => "removal_of_defender",
+        }
+    }
+}
+
+pub struct Motif {
+    kind: Kind,
+    m: chess_engine::Move,
+    /// The piece carrying out the tactic, where it stands after the move.
+    piece: Piece,
+    /// The pieces it's aimed at, nearest first.
+    targets: Vec<Piece>,
+}
+
+frozen_wrap!(ref Motif, "Silverpoint::Motif");
+
+unsafe impl magnus::IntoValueFromNative for Motif {}
+
+fn squares(board: &chess_engine::Board, m: chess_engine::Move) -> Option<(Position, Position)> {
+    let king = Position::king_pos(board.get_turn_color());
+    match m {
+        chess_engine::Move::Piece(from, to) => Some((from, to)),
+        chess_engine::Move::KingSideCastle => {
+            Some((Position::new(king.get_row(), 7), king.next_right()))
+        }
+        chess_engine::Move::QueenSideCastle => {
+            Some((Position::new(king.get_row(), 0), king.next_left()))
+        }
+        chess_engine::Move::Resign => None,
+    }
+}
+
+fn is_undefended(board: &chess_engine::Board, square: usize, piece: Piece) -> bool {
+    attacks::attackers_with(board, square, piece.get_color(), attacks::occupancy(board))
+        .next()
+        .is_none()
+}
+
+/// Whether `attacker` going for `target` on `square` is a real threat: the target is the
+/// king, worth more than the attacker, or has nothing defending it.
+fn is_threat(board: &chess_engine::Board, attacker: Piece, square: usize, target: Piece) -> bool {
+    target.is_king()
+        || target.get_material_value() > attacker.get_material_value()
+        || is_undefended(board, square, target)
+}
+
+fn threats(board: &chess_engine::Board, piece: Piece, square: usize, color: Color) -> Vec<Piece> {
+    let attacked = attacks::attacks(piece, square, attacks::occupancy(board));
+    let mut threats = attacks::pieces(board)
+        .filter(|&(target_square, target)| {
+            attacked & 1 << target_square != 0
+                && target.get_color() == color
+                && is_threat(board, piece, target_square, target)
+        })
+        .collect::<Vec<_>>();
+
+    threats.sort_by_key(|&(target_square, _)| geometry::distance(square, target_square));
+    threats.into_iter().map(|(_, target)| target).collect()
+}
+
+fn line_up(
+    board: &chess_engine::Board,
+    square: usize,
+    direction: Direction,
+) -> Option<(Piece, Piece)> {
+    let mut met = geometry::ray(square, direction)
+        .filter_map(|square| board.get_piece(from_square_index(square)));
+    Some((met.next()?, met.next()?))
+}
+
+fn pins_and_skewers(
+    next: &chess_engine::Board,
+    piece: Piece,
+    square: usize,
+    motif: &impl Fn(Kind, Vec<Piece>) -> Motif,
+) -> Vec<Motif> {
+    let enemy = !piece.get_color();
+
+    Direction::ALL
+        .into_iter()
+        .filter(|&direction| attacks::slides_along(piece, direction))
+        .filter_map(|direction| line_up(next, square, direction))
+        .filter(|(front, back)| front.get_color() == enemy && back.get_color() == enemy)
+        .filter_map(|(front, back)| {
+            let kind = if back.is_king() {
+                Kind::Pin
+            } else if front.is_king() {
+                Kind::Skewer
+            } else {
+                match front.get_material_value().cmp(&back.get_material_value()) {
+                    std::cmp::Ordering::Less => Kind::Pin,
+                    std::cmp::Ordering::Greater => Kind::Skewer,
+                    std::cmp::Ordering::Equal => return None,
+                }
+            };
+            Some(motif(kind, vec![front, back]))
+        })
+        .collect()
+}
+
+fn discovered_attacks(
+    board: &chess_engine::Board,
+    next: &chess_engine::Board,
+    from: usize,
+    color: Color,
+    m: chess_engine::Move,
+) -> Vec<Motif> {
+    attacks::pieces(next)
+        .filter(|&(square, slider)| {
+            slider.get_color() == color
+                && board.get_piece(from_square_index(square)) == Some(slider)
+        })
+        .filter_map(|(square, slider)| {
+            let targets = threats(next, slider, square, !color)
+                .into_iter()
+                .filter(|target| {
+                    geometry::between(square, square_index(target.get_pos()))
+                        .any(|between| between == from)
+                })
+                .collect::<Vec<_>>();
+
+            (!targets.is_empty()).then_some(Motif {
+                kind: Kind::DiscoveredAttack,
+                m,
+                piece: slider,
+                targets,
+            })
+        })
+        .collect()
+}
+
+fn is_boxed_in(next: &chess_engine::Board, king: Piece) -> bool {
+    let color = king.get_color();
+    let back_rank = Position::king_pos(color).get_row();
+    let flights = geometry::king_attacks(square_index(king.get_pos()));
+
+    king.get_pos().get_row() == back_rank
+        && (0..64)
+            .filter(|&square| flights & 1 << square != 0)
+            .map(from_square_index)
+            .filter(|square| square.get_row() != back_rank)
+            .all(|square| next.has_ally_piece(square, color) || next.is_threatened(square, color))
+}
+
+fn removed_defences(
+    board: &chess_engine::Board,
+    next: &chess_engine::Board,
+    square: usize,
+    captured: Piece,
+) -> Vec<Piece> {
+    let enemy = captured.get_color();
+    let occupied = attacks::occupancy(board);
+
+    attacks::pieces(board)
+        .filter(|&(defended, piece)| {
+            defended != square
+                && piece.get_color() == enemy
+                && attacks::attackers_with(board, defended, enemy, occupied)
+                    .any(|(defender, _)| defender == square)
+        })
+        .filter(|&(defended, piece)| {
+            next.get_piece(from_square_index(defended)) == Some(piece)
+                && !attacks::attackers(next, defended, !enemy).is_empty()
+                && is_undefended(next, defended, piece)
+        })
+        .map(|(_, piece)| piece)
+        .collect()
+}
+
+fn detect(board: &chess_engine::Board, m: chess_engine::Move) -> Vec<Motif> {
+    let Some((from, to)) = squares(board, m) else {
+        return Vec::new();
+    };
+    let next = board.apply_eval_move(m);
+    let Some(piece) = next.get_piece(to) else {
+        return Vec::new();
+    };
+    let color = piece.get_color();
+    let (from_square, to_square) = (square_index(from), square_index(to));
+    let motif = |kind, targets| Motif {
+        kind,
+        m,
+        piece,
+        targets,
+    };
+
+    let mut motifs = Vec::new();
+
+    let forked = threats(&next, piece, to_square, !color);
+    if forked.len() >= 2 {
+        motifs.push(motif(Kind::Fork, forked));
+    }
+
+    motifs.extend(pins_and_skewers(&next, piece, to_square, &motif));
+    motifs.extend(discovered_attacks(board, &next, from_square, color, m));
+
+    let king = next
+        .get_king_pos(!color)
+        .and_then(|king| next.get_piece(king));
+    if let Some(king) = king {
+        if attacks::checkers(&next, !color).len() >= 2 {
+            motifs.push(motif(Kind::DoubleCheck, vec![king]));
+        }
+    }
+
+    // Only en passant takes diagonally onto an empty square.
+    let en_passant = piece.is_pawn() && from.get_col() != to.get_col() && board.has_no_piece(to);
+    let capture_square = if en_passant { to.pawn_back(color) } else { to };
+    let captured = board
+        .get_piece(capture_square)
+        .filter(|captured| captured.get_color() != color);
+    if let Some(captured) = captured {
+        if attacks::attackers(&next, to_square, !color).is_empty() {
+            motifs.push(motif(Kind::HangingPiece, vec![captured]));
+        }
+    }
+
+    if let Some(king) = king {
+        let checks_along_rank = matches!(piece, Piece::Rook(..) | Piece::Queen(..))
+            && to.get_row() == king.get_pos().get_row()
+            && attacks::checkers(&next, !color).contains(&piece);
+        if checks_along_rank && is_boxed_in(&next, king) {
+            motifs.push(motif(Kind::BackRank, vec![king]));
+        }
+    }
+
+    if let Some(captured) = captured {
+        let undefended = removed_defences(board, &next, square_index(capture_square), captured);
+        if !undefended.is_empty() {
+            motifs.push(motif(Kind::RemovalOfDefender, undefended));
+        }
+    }
+
+    motifs
+}
+
+impl Motif {
+    fn detect(board: &Board, &m: &Move) -> Result<Vec<Self>, magnus::Error> {
+        let board = chess_engine::Board::from(*board);
+        let m = m.into();
+        if m == chess_engine::Move::Resign {
+            return Ok(Vec::new());
+        }
+
+        no_gvl!(legal_moves(&board).contains(&m).then(|| detect(&board, m)))?
+            .ok_or_else(|| errors::illegal_move(format!("illegal move {m}"), m, board))
+    }
+
+    fn kind(&self) -> Symbol {
+        Symbol::new(self.kind.name())
+    }
+
+    fn get_move(&self) -> Move {
+        self.m.into()
+    }
+
+    fn piece(&self) -> enums::Piece {
+        self.piece.into()
+    }
+
+    fn targets(&self) -> Vec<enums::Piece> {
+        self.targets.iter().copied().map(Into::into).collect()
+    }
+
+    fn inspect(&self) -> String {
+        let targets = self
+            .targets
+            .iter()
+            .map(|target| format!("{} {}", target.get_name(), target.get_pos()))
+            .collect::<Vec<_>>();
+        format!(
+            "#<Silverpoint::Motif {} by {} {} on {}>",
+            self.kind.name(),
+            self.piece.get_name(),
+            self.piece.get_pos(),
+            targets.join(", ")
+        )
+    }
+}
+
+pub fn bind(ruby: &magnus::Ruby, module: impl Module) -> Result<(), magnus::Error> {
+    let tactics = module.define_module("Tactics")?;
+    tactics.define_singleton_method("detect", function!(Motif::detect, 2))?;
+
+    let class = module.define_class("Motif", ruby.class_object())?;
+    class.define_method("kind", method!(Motif::kind, 0))?;
+    class.define_method("move", method!(Motif::get_move, 0))?;
+    class.define_method("piece", method!(Motif::piece, 0))?;
+    class.define_method("targets", method!(Motif::targets, 0))?;
+    class.define_method("inspect", method!(Motif::inspect, 0))?;
+
+    Ok(())
+}
@@ -2,5 +2,4 @@
 
 require_relative "silverpoint/version"
 require_relative "silverpoint/silverpoint"
-
-
+require_relative "silverpoint/json"
@@ -0,0 +1,38 @@
+# frozen_string_literal: true
+
+require "json"
+
+module Silverpoint
+  # JSON goes through the hash forms, so anything `to_h` writes `from_h` reads back.
+  [Board, Move, Position, Piece].each do |klass|
+    klass.class_eval do
+      def as_json(*)
+        to_h
+      end
+
+      def to_json(*args)
+        as_json.to_json(*args)
+      end
+    end
+
+    klass.define_singleton_method(:from_json) { |json| from_h(JSON.parse(json)) }
+  end
+
+  class Color
+    def as_json(*)
+      to_s.downcase
+    end
+
+    def to_json(*args)
+      as_json.to_json(*args)
+    end
+
+    def self.from_json(json)
+      case JSON.parse(json)
+      when "white" then White
+      when "black" then Black
+      else raise ArgumentError, "unknown color #{json}"
+      end
+    end
+  end
+end
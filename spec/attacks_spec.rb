@@ -0,0 +1,63 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "attack detection" do
+  def pgn(square)
+    Silverpoint::Position.pgn(square)
+  end
+
+  def squares(*names)
+    names.map { |name| pgn(name) }
+  end
+
+  it "finds the pieces giving check" do
+    board = board_from_fen("4k3/8/8/1B6/8/8/8/4R1K1 b - - 0 1")
+
+    expect(board.checkers(Silverpoint::Color::Black)).to contain_exactly(board.piece(pgn("e1")), board.piece(pgn("b5")))
+    expect(board.checkers(Silverpoint::Color::White)).to be_empty
+  end
+
+  it "finds pinned pieces with their pinner and ray" do
+    board = board_from_fen("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1")
+    pins = board.pinned_pieces(Silverpoint::Color::White)
+
+    expect(pins.size).to eq(1)
+    expect(pins.first.piece).to eq(board.piece(pgn("e2")))
+    expect(pins.first.pinner).to eq(board.piece(pgn("e7")))
+    expect(pins.first.king).to eq(pgn("e1"))
+    expect(pins.first.ray).to eq(squares("e2", "e3", "e4", "e5", "e6", "e7"))
+    expect(board.pinned_pieces(Silverpoint::Color::Black)).to be_empty
+  end
+
+  it "finds discovered attacks on the king" do
+    board = board_from_fen("4k3/8/8/8/8/8/4N3/4R1K1 w - - 0 1")
+    discovered = board.discovered_attackers
+
+    expect(discovered.size).to eq(1)
+    expect(discovered.first.piece).to eq(board.piece(pgn("e2")))
+    expect(discovered.first.attacker).to eq(board.piece(pgn("e1")))
+    expect(discovered.first.target).to eq(pgn("e8"))
+    expect(discovered.first.ray).to eq(squares("e7", "e6", "e5", "e4", "e3", "e2", "e1"))
+  end
+
+  it "finds discovered attacks on undefended or more valuable pieces" do
+    undefended = board_from_fen("6k1/8/5n2/8/8/2N5/8/B5K1 w - - 0 1")
+    valuable = board_from_fen("6k1/6p1/5r2/8/8/2N5/8/B5K1 w - - 0 1")
+
+    [undefended, valuable].each do |board|
+      discovered = board.discovered_attackers
+
+      expect(discovered.size).to eq(1)
+      expect(discovered.first.piece).to eq(board.piece(pgn("c3")))
+      expect(discovered.first.attacker).to eq(board.piece(pgn("a1")))
+      expect(discovered.first.target).to eq(pgn("f6"))
+    end
+  end
+
+  it "ignores discovered attacks on defended pieces worth no more than the attacker" do
+    board = board_from_fen("6k1/6p1/5n2/8/8/2N5/8/B5K1 w - - 0 1")
+
+    expect(board.discovered_attackers).to be_empty
+  end
+end
@@ -0,0 +1,163 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+# Presses made back to back use next to no time, so a clock only needs to run for real to
+# flag while it's running. Everything else is checked against the times it was set up with.
+RSpec.describe Silverpoint::Clock do
+  let(:white) { Silverpoint::Color::White }
+  let(:black) { Silverpoint::Color::Black }
+
+  def press(clock, times)
+    times.times { clock.press }
+    clock
+  end
+
+  describe ".parse" do
+    it "reads the base time in minutes and the increment in seconds" do
+      clock = described_class.parse("5+3")
+
+      expect(clock.remaining(white)).to eq(300)
+      expect(clock.remaining(black)).to eq(300)
+      expect(press(clock, 2).remaining(white)).to be_within(0.5).of(303)
+    end
+
+    it "reads a stage with a move count" do
+      expect(described_class.parse("40/90+30").remaining(white)).to eq(5400)
+    end
+
+    it "reads a Bronstein delay" do
+      clock = described_class.parse("15d10")
+
+      expect(clock.remaining(white)).to eq(900)
+      expect(press(clock, 2).remaining(white)).to eq(900)
+    end
+
+    it "starts with the first of several stages" do
+      expect(described_class.parse("40/90+30, 30+30").remaining(white)).to eq(5400)
+    end
+
+    it "raises ArgumentError for anything else" do
+      ["", "abc", "40/", "5+x", "-5", "5,"].each do |control|
+        expect { described_class.parse(control) }.to raise_error(ArgumentError)
+      end
+    end
+  end
+
+  describe ".new" do
+    it "takes the time in seconds" do
+      expect(described_class.new(300).remaining(white)).to eq(300)
+    end
+
+    it "raises ArgumentError for a negative time" do
+      expect { described_class.new(-1) }.to raise_error(ArgumentError)
+      expect { described_class.new(60, increment: -1) }.to raise_error(ArgumentError)
+    end
+  end
+
+  describe "#press" do
+    it "starts a stopped clock for white without counting a move" do
+      clock = press(described_class.new(60, increment: 2), 1)
+
+      expect(clock).to be_running
+      expect(clock.turn).to eq(white)
+      expect(clock.moves(white)).to eq(0)
+      expect(clock.remaining(white)).to be_within(0.5).of(60)
+    end
+
+    it "adds the increment after each move and hands over the turn" do
+      clock = press(described_class.new(60, increment: 2), 2)
+
+      expect(clock.turn).to eq(black)
+      expect(clock.moves(white)).to eq(1)
+      expect(clock.remaining(white)).to be_within(0.5).of(62)
+      expect(clock.remaining(black)).to be_within(0.5).of(60)
+    end
+
+    it "gives back the time a move used, up to the delay" do
+      clock = press(described_class.new(60, delay: 5), 2)
+
+      expect(clock.remaining(white)).to eq(60)
+      expect(clock.remaining(black)).to be_within(0.5).of(60)
+    end
+
+    it "adds the next stage's time once a stage's moves are made" do
+      clock = press(described_class.parse("2/1, 1"), 5)
+
+      expect(clock.moves(white)).to eq(2)
+      expect(clock.moves(black)).to eq(2)
+      expect(clock.remaining(white)).to be_within(0.5).of(120)
+      expect(clock.remaining(black)).to be_within(0.5).of(120)
+
+      # The last stage is sudden death, so no more time comes.
+      expect(press(clock, 4).remaining(white)).to be_within(0.5).of(120)
+    end
+
+    it "repeats a last stage that has a move count" do
+      clock = press(described_class.parse("1/1"), 4)
+
+      expect(clock.remaining(white)).to be_within(0.5).of(180)
+      expect(clock.remaining(black)).to be_within(0.5).of(120)
+    end
+  end
+
+  describe "flag fall" do
+    it "flags a side with no time left" do
+      clock = described_class.new(0)
+
+      expect(clock).to be_flag
+      expect(clock.flagged).to eq(white)
+    end
+
+    it "ignores presses once a flag has fallen" do
+      clock = press(described_class.new(0), 2)
+
+      expect(clock).not_to be_running
+      expect(clock.moves(white)).to eq(0)
+      expect(clock.turn).to eq(white)
+    end
+
+    it "flags the running side when its time runs out" do
+      clock = press(described_class.new(0.2), 2)
+      expect(clock).not_to be_flag
+
+      sleep 0.3
+
+      expect(clock.flagged).to eq(black)
+      expect(clock.remaining(black)).to eq(0)
+      expect(clock).not_to be_running
+    end
+
+    it "doesn't flag a stopped clock" do
+      clock = press(described_class.new(0.2), 2)
+      clock.stop
+
+      sleep 0.3
+
+      expect(clock).not_to be_flag
+      expect(clock.flagged).to be_nil
+    end
+  end
+
+  describe "#result" do
+    it "is nil while both sides have time" do
+      expect(described_class.new(60).result(Silverpoint::Board.new)).to be_nil
+    end
+
+    it "loses on time" do
+      result = described_class.new(0).result(Silverpoint::Board.new)
+
+      expect(result.reason).to eq(:timeout)
+      expect(result.winning_color).to eq(black)
+      expect(result.score).to eq("0-1")
+    end
+
+    it "draws when the other side couldn't mate" do
+      result = described_class.new(0).result(board_from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1"))
+
+      expect(result.reason).to eq(:timeout)
+      expect(result).to be_draw
+      expect(result.score).to eq("1/2-1/2")
+    end
+  end
+end
@@ -0,0 +1,110 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Engine do
+  # White's rook can take an undefended queen, which is better than anything else by far.
+  let(:board) { board_from_fen("k7/8/8/3q4/8/8/8/K2R4 w - - 0 1") }
+  let(:capture) { Silverpoint::Move.parse("d1d5") }
+
+  # Hands out `values` in order, one per call to `rand`.
+  def random(*values)
+    Object.new.tap do |random|
+      random.define_singleton_method(:rand) { values.shift or raise "rand called too often" }
+    end
+  end
+
+  describe "skill levels and ratings" do
+    it "plays at full strength by default" do
+      engine = described_class.new
+
+      expect(engine.skill_level).to eq(described_class::MAX_SKILL_LEVEL)
+      expect(engine.elo).to eq(described_class::MAX_ELO)
+    end
+
+    it "rates each level 60 above the one below it, from 800" do
+      expect(described_class.new(skill_level: 0).elo).to eq(described_class::MIN_ELO)
+      expect(described_class.new(skill_level: 5).elo).to eq(1100)
+      expect(described_class.new(skill_level: 20).elo).to eq(2000)
+    end
+
+    it "sets the level nearest below a rating" do
+      engine = described_class.new(elo: 1430)
+      expect(engine.skill_level).to eq(10)
+      expect(engine.elo).to eq(1400)
+
+      engine.elo = 800
+      expect(engine.skill_level).to eq(0)
+      engine.elo = 2000
+      expect(engine.skill_level).to eq(20)
+    end
+
+    it "raises ArgumentError for a level or rating out of range" do
+      engine = described_class.new(skill_level: 7)
+
+      [-1, 21].each { |level| expect { engine.skill_level = level }.to raise_error(ArgumentError) }
+      [799, 2001].each { |elo| expect { engine.elo = elo }.to raise_error(ArgumentError) }
+      expect { described_class.new(skill_level: 21) }.to raise_error(ArgumentError)
+      expect { described_class.new(elo: 0) }.to raise_error(ArgumentError)
+      expect(engine.skill_level).to eq(7)
+    end
+  end
+
+  describe "#best_move" do
+    it "plays the best move at the top level without drawing a random number" do
+      expect(described_class.new.best_move(board, random: random)).to eq(capture)
+    end
+
+    it "plays the best move when the noise favors it" do
+      engine = described_class.new(skill_level: 0)
+
+      expect(engine.best_move(board, random: random(0.5, 0.0, 0.0, 0.0, 0.0))).to eq(capture)
+    end
+
+    it "lets the noise pick a worse move at the lowest level" do
+      engine = described_class.new(skill_level: 0)
+      move = engine.best_move(board, random: random(0.5, 0.0, 1.0, 0.0, 0.0))
+
+      expect(move).not_to eq(capture)
+      expect(board.legal_moves).to include(move)
+    end
+
+    it "doesn't let the noise give up a queen just below the top level" do
+      engine = described_class.new(skill_level: 19)
+
+      expect(engine.best_move(board, random: random(0.5, 0.0, 1.0, 1.0, 1.0))).to eq(capture)
+    end
+
+    # The first draw decides whether to blunder: level 0 blunders a fifth of the time and
+    # level 10 a twentieth. The second then picks the move, favoring small losses.
+    it "blunders when the first draw falls under the level's chance" do
+      engine = described_class.new(skill_level: 0)
+      move = engine.best_move(board, random: random(0.19, 0.999))
+      expect(move).not_to eq(capture)
+      expect(board.legal_moves).to include(move)
+
+      engine.skill_level = 10
+      expect(engine.best_move(board, random: random(0.04, 0.999))).not_to eq(capture)
+    end
+
+    it "doesn't blunder when the first draw reaches the level's chance" do
+      engine = described_class.new(skill_level: 0)
+      expect(engine.best_move(board, random: random(0.2, 0.0, 0.0, 0.0, 0.0))).to eq(capture)
+
+      engine.skill_level = 10
+      expect(engine.best_move(board, random: random(0.05, 0.0, 0.0, 0.0, 0.0))).to eq(capture)
+    end
+
+    it "still favors the best move when blundering" do
+      engine = described_class.new(skill_level: 0)
+
+      expect(engine.best_move(board, random: random(0.0, 0.0))).to eq(capture)
+    end
+
+    it "resigns without a legal move" do
+      stalemate = board_from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1")
+
+      expect(described_class.new(skill_level: 0).best_move(stalemate, random: random)).to be_resign
+    end
+  end
+end
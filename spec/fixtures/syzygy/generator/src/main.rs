@@ -0,0 +1,461 @@
+//! Writes the KQvK and KRvK tables the tablebase specs probe.
+//!
+//! Both endgames are solved here by retrograde analysis and written in the Syzygy format, with
+//! a code of the same length for every value to keep the writer short. Real tables Huffman
+//! code runs of values as symbol pairs, but share the rest of the format: the header, the
+//! position indices, the sparse index and the blocks.
+//!
+//! Rewrite the fixtures with `cargo run --release` from this directory.
+
+use std::{fs, path::Path};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+/// The table holds both sides to move, which is only left out of symmetric tables.
+const TABLE_SPLIT: u8 = 1;
+
+const QUEEN: u8 = 5;
+const ROOK: u8 = 4;
+const KING: u8 = 6;
+const BLACK: u8 = 8;
+
+const WDL_LOSS: u8 = 0;
+const WDL_DRAW: u8 = 2;
+const WDL_WIN: u8 = 4;
+
+/// 64-byte blocks, and an entry in the sparse index every 64 values.
+const BLOCK_BITS: u8 = 6;
+const SPAN_BITS: u8 = 6;
+
+/// Placements of three unique pieces with the first in the a1-d1-d4 triangle.
+const SIZE: usize = 31332;
+
+fn rank(sq: usize) -> usize {
+    sq / 8
+}
+
+fn file(sq: usize) -> usize {
+    sq % 8
+}
+
+fn touching(a: usize, b: usize) -> bool {
+    rank(a).abs_diff(rank(b)) <= 1 && file(a).abs_diff(file(b)) <= 1
+}
+
+fn king_moves(from: usize) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |&to| to != from && touching(from, to))
+}
+
+/// The squares a queen or rook attacks, stopping at any of `blockers`.
+fn attacks(piece: u8, from: usize, blockers: &[usize]) -> Vec<usize> {
+    let mut directions = vec![(1, 0), (-1, 0), (0, 1), (0, -1)];
+    if piece == QUEEN {
+        directions.extend([(1, 1), (1, -1), (-1, 1), (-1, -1)]);
+    }
+
+    let mut squares = Vec::new();
+    for (dr, df) in directions {
+        let (mut r, mut f) = (rank(from) as i32, file(from) as i32);
+        loop {
+            r += dr;
+            f += df;
+            if !(0..8).contains(&r) || !(0..8).contains(&f) {
+                break;
+            }
+
+            let sq = (r * 8 + f) as usize;
+            squares.push(sq);
+            if blockers.contains(&sq) {
+                break;
+            }
+        }
+    }
+
+    squares
+}
+
+/// A position with white's king, black's king and white's piece.
+#[derive(Clone, Copy)]
+struct Position {
+    white_king: usize,
+    black_king: usize,
+    piece: usize,
+}
+
+impl Position {
+    fn all() -> impl Iterator<Item = Self> {
+        (0..64 * 64 * 64).map(|n| Self {
+            white_king: n / 4096,
+            black_king: n / 64 % 64,
+            piece: n % 64,
+        })
+    }
+
+    fn slot(self) -> usize {
+        (self.white_king * 64 + self.black_king) * 64 + self.piece
+    }
+
+    fn is_legal(self) -> bool {
+        let Self {
+            white_king,
+            black_king,
+            piece,
+        } = self;
+
+        white_king != piece && black_king != piece && !touching(white_king, black_king)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Illegal,
+    Unknown,
+    Draw,
+    /// White mates in this many of its moves.
+    Mate(u8),
+}
+
+struct Solution {
+    white_to_move: Vec<Outcome>,
+    black_to_move: Vec<Outcome>,
+}
+
+enum BlackMove {
+    Capture,
+    To(Position),
+}
+
+fn black_moves(piece: u8, pos: Position) -> Vec<BlackMove> {
+    let guarded = attacks(piece, pos.piece, &[pos.white_king]);
+
+    king_moves(pos.black_king)
+        .filter(|&to| !touching(to, pos.white_king))
+        .filter_map(|to| {
+            if to == pos.piece {
+                (!touching(to, pos.white_king)).then_some(BlackMove::Capture)
+            } else {
+                (!guarded.contains(&to)).then_some(BlackMove::To(Position {
+                    black_king: to,
+                    ..pos
+                }))
+            }
+        })
+        .collect()
+}
+
+fn white_moves(piece: u8, pos: Position) -> Vec<Position> {
+    let king = king_moves(pos.white_king)
+        .filter(|&to| to != pos.piece && !touching(to, pos.black_king))
+        .map(|to| Position {
+            white_king: to,
+            ..pos
+        });
+    let others = attacks(piece, pos.piece, &[pos.white_king, pos.black_king])
+        .into_iter()
+        .filter(|&to| to != pos.white_king && to != pos.black_king)
+        .map(|to| Position { piece: to, ..pos });
+
+    king.chain(others).collect()
+}
+
+/// Work back from the mates, one white move at a time, until nothing changes.
+fn solve(piece: u8) -> Solution {
+    let mut white_to_move = vec![Outcome::Illegal; 64 * 64 * 64];
+    let mut black_to_move = vec![Outcome::Illegal; 64 * 64 * 64];
+
+    for pos in Position::all().filter(|pos| pos.is_legal()) {
+        let check = attacks(piece, pos.piece, &[pos.white_king]).contains(&pos.black_king);
+        if !check {
+            white_to_move[pos.slot()] = Outcome::Unknown;
+        }
+
+        let moves = black_moves(piece, pos);
+        black_to_move[pos.slot()] = if moves.iter().any(|m| matches!(m, BlackMove::Capture)) {
+            Outcome::Draw
+        } else if moves.is_empty() {
+            if check {
+                Outcome::Mate(0)
+            } else {
+                Outcome::Draw
+            }
+        } else {
+            Outcome::Unknown
+        };
+    }
+
+    for moves in 1.. {
+        let mut changed = false;
+
+        for pos in Position::all() {
+            if white_to_move[pos.slot()] == Outcome::Unknown
+                && white_moves(piece, pos)
+                    .iter()
+                    .any(|next| black_to_move[next.slot()] == Outcome::Mate(moves - 1))
+            {
+                white_to_move[pos.slot()] = Outcome::Mate(moves);
+                changed = true;
+            }
+        }
+
+        for pos in Position::all() {
+            if black_to_move[pos.slot()] != Outcome::Unknown {
+                continue;
+            }
+
+            // Lost once every move is, as slowly as possible.
+            let mut longest = Some(0);
+            for m in black_moves(piece, pos) {
+                let BlackMove::To(next) = m else {
+                    unreachable!("positions with a capture are draws");
+                };
+                longest = match white_to_move[next.slot()] {
+                    Outcome::Mate(n) => longest.map(|longest: u8| longest.max(n)),
+                    _ => None,
+                };
+                if longest.is_none() {
+                    break;
+                }
+            }
+
+            if let Some(longest) = longest {
+                black_to_move[pos.slot()] = Outcome::Mate(longest);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    for outcome in white_to_move.iter_mut().chain(&mut black_to_move) {
+        if *outcome == Outcome::Unknown {
+            *outcome = Outcome::Draw;
+        }
+    }
+
+    Solution {
+        white_to_move,
+        black_to_move,
+    }
+}
+
+fn diagonal_offset(sq: usize) -> i32 {
+    rank(sq) as i32 - file(sq) as i32
+}
+
+/// Where the table keeps a position, given white's king, white's piece and black's king.
+fn index(mut squares: [usize; 3]) -> usize {
+    // Move white's king into the a1-d1-d4 triangle, and the first piece off the diagonal
+    // below it.
+    if file(squares[0]) > 3 {
+        squares = squares.map(|sq| sq ^ 7);
+    }
+    if rank(squares[0]) > 3 {
+        squares = squares.map(|sq| sq ^ 0o70);
+    }
+    if squares
+        .iter()
+        .find(|&&sq| diagonal_offset(sq) != 0)
+        .is_some_and(|&sq| diagonal_offset(sq) > 0)
+    {
+        squares = squares.map(|sq| (sq >> 3) | ((sq & 7) << 3));
+    }
+
+    let below: Vec<usize> = (0..64).filter(|&sq| diagonal_offset(sq) < 0).collect();
+    let below_diagonal = |sq| below.iter().position(|&s| s == sq).unwrap();
+    let triangle = |sq| {
+        [1, 2, 3, 10, 11, 19, 0, 9, 18, 27]
+            .iter()
+            .position(|&s| s == sq)
+            .unwrap()
+    };
+
+    let [s0, s1, s2] = squares;
+    let before1 = usize::from(s1 > s0);
+    let before2 = usize::from(s2 > s0) + usize::from(s2 > s1);
+    if diagonal_offset(s0) != 0 {
+        (triangle(s0) * 63 + s1 - before1) * 62 + s2 - before2
+    } else if diagonal_offset(s1) != 0 {
+        (6 * 63 + rank(s0) * 28 + below_diagonal(s1)) * 62 + s2 - before2
+    } else if diagonal_offset(s2) != 0 {
+        let rank1 = rank(s1) - before1;
+        6 * 63 * 62 + 4 * 28 * 62 + (rank(s0) * 7 + rank1) * 28 + below_diagonal(s2)
+    } else {
+        let (rank1, rank2) = (rank(s1) - before1, rank(s2) - before2);
+        6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + (rank(s0) * 7 + rank1) * 6 + rank2
+    }
+}
+
+/// Store a value, checking it agrees with any other position at the same index.
+fn store(values: &mut [Option<u8>], idx: usize, value: u8) {
+    let slot = &mut values[idx];
+    assert!(
+        slot.is_none_or(|old| old == value),
+        "conflicting values at {idx}"
+    );
+    *slot = Some(value);
+}
+
+/// Fill the indices no position uses with the commonest value, which compresses best.
+fn fill(values: Vec<Option<u8>>) -> Vec<u8> {
+    let mut counts = [0; 256];
+    for value in values.iter().flatten() {
+        counts[usize::from(*value)] += 1;
+    }
+    let commonest = (0..=255).max_by_key(|&value| counts[usize::from(value)]).unwrap();
+
+    values
+        .into_iter()
+        .map(|value| value.unwrap_or(commonest))
+        .collect()
+}
+
+/// One side to move of a table, in the sections the format lays out separately.
+struct Part {
+    header: Vec<u8>,
+    sparse_index: Vec<u8>,
+    block_lengths: Vec<u8>,
+    blocks: Vec<u8>,
+}
+
+/// Code each distinct value as its position among them, in as few bits as that takes.
+fn encode(values: &[u8]) -> Part {
+    let mut symbols = values.to_vec();
+    symbols.sort_unstable();
+    symbols.dedup();
+    let bits = (usize::BITS - (symbols.len() - 1).leading_zeros()).max(1) as usize;
+    let block_size = 1 << BLOCK_BITS;
+    let per_block = block_size * 8 / bits;
+    let blocks: Vec<&[u8]> = values.chunks(per_block).collect();
+
+    let mut header = vec![0, BLOCK_BITS, SPAN_BITS, 0];
+    header.extend((blocks.len() as u32).to_le_bytes());
+    // Every code has the same length, so the one length's codes start at the first symbol.
+    header.extend([bits as u8, bits as u8]);
+    header.extend(0u16.to_le_bytes());
+    header.extend((symbols.len() as u16).to_le_bytes());
+    for &symbol in &symbols {
+        // A plain value, rather than a pair of symbols.
+        header.extend([symbol, 0xF0, 0xFF]);
+    }
+    if symbols.len() % 2 == 1 {
+        header.push(0);
+    }
+
+    // Each sparse index entry points at the middle value of its span.
+    let span = 1 << SPAN_BITS;
+    let mut sparse_index = Vec::new();
+    for entry in 0..values.len().div_ceil(span) {
+        let middle = entry * span + span / 2;
+        sparse_index.extend(((middle / per_block) as u32).to_le_bytes());
+        sparse_index.extend(((middle % per_block) as u16).to_le_bytes());
+    }
+
+    let mut block_lengths = Vec::new();
+    let mut data = Vec::new();
+    for block in blocks {
+        block_lengths.extend(((block.len() - 1) as u16).to_le_bytes());
+
+        let mut bytes = vec![0u8; block_size];
+        for (n, value) in block.iter().enumerate() {
+            let code = symbols.binary_search(value).unwrap();
+            for bit in 0..bits {
+                if code >> (bits - 1 - bit) & 1 == 1 {
+                    let at = n * bits + bit;
+                    bytes[at / 8] |= 0x80 >> (at % 8);
+                }
+            }
+        }
+        data.extend(bytes);
+    }
+
+    Part {
+        header,
+        sparse_index,
+        block_lengths,
+        blocks: data,
+    }
+}
+
+fn write_table(magic: [u8; 4], piece: u8, parts: &[Part]) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.push(TABLE_SPLIT);
+    // The three pieces form one group, which comes first. Each piece is given for both sides
+    // to move, in the low and high halves of its byte.
+    out.push(0);
+    for code in [KING, piece, KING | BLACK] {
+        out.push(code | (code << 4));
+    }
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+
+    for part in parts {
+        out.extend(&part.header);
+    }
+    for part in parts {
+        out.extend(&part.sparse_index);
+    }
+    for part in parts {
+        out.extend(&part.block_lengths);
+    }
+    for part in parts {
+        out.resize(out.len().next_multiple_of(64), 0);
+        out.extend(&part.blocks);
+    }
+
+    out
+}
+
+fn main() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+    for (piece, name) in [(QUEEN, "KQvK"), (ROOK, "KRvK")] {
+        let solution = solve(piece);
+        let mut wdl = [vec![None; SIZE], vec![None; SIZE]];
+        // Only white to move is kept, counting white's moves to the mate less one.
+        let mut dtz = vec![None; SIZE];
+
+        for pos in Position::all().filter(|pos| pos.is_legal()) {
+            let idx = index([pos.white_king, pos.piece, pos.black_king]);
+            match solution.white_to_move[pos.slot()] {
+                Outcome::Mate(n) => {
+                    store(&mut wdl[0], idx, WDL_WIN);
+                    store(&mut dtz, idx, n - 1);
+                }
+                Outcome::Draw => store(&mut wdl[0], idx, WDL_DRAW),
+                Outcome::Illegal | Outcome::Unknown => {}
+            }
+            match solution.black_to_move[pos.slot()] {
+                Outcome::Mate(_) => store(&mut wdl[1], idx, WDL_LOSS),
+                Outcome::Draw => store(&mut wdl[1], idx, WDL_DRAW),
+                Outcome::Illegal | Outcome::Unknown => {}
+            }
+        }
+
+        let [white, black] = wdl.map(|values| encode(&fill(values)));
+        fs::write(
+            dir.join(format!("{name}.rtbw")),
+            write_table(WDL_MAGIC, piece, &[white, black]),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(format!("{name}.rtbz")),
+            write_table(DTZ_MAGIC, piece, &[encode(&fill(dtz))]),
+        )
+        .unwrap();
+
+        let longest = solution
+            .white_to_move
+            .iter()
+            .filter_map(|outcome| match outcome {
+                Outcome::Mate(n) => Some(*n),
+                _ => None,
+            })
+            .max()
+            .unwrap();
+        println!("{name}: the longest mate takes {longest} moves");
+    }
+}
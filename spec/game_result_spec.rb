@@ -0,0 +1,109 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::GameResult do
+  def play(fen, m)
+    board_from_fen(fen).play_move(Silverpoint::Move.parse(m))
+  end
+
+  it "is decisive after a resignation" do
+    result = Silverpoint::Board.new.play_move(Silverpoint::Move.new_resign)
+
+    expect(result.reason).to eq(:resignation)
+    expect(result.score).to eq("0-1")
+    expect(result.winning_color).to eq(Silverpoint::Color::Black)
+    expect(result).to be_decisive
+    expect(result).not_to be_draw
+    expect(result).not_to be_stalemate
+  end
+
+  it "is decisive after a checkmate" do
+    result = play("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2", "d8h4")
+
+    expect(result.reason).to eq(:checkmate)
+    expect(result.score).to eq("0-1")
+    expect(result).to be_decisive
+    expect(result).not_to be_draw
+  end
+
+  it "is a draw after a stalemate" do
+    result = play("7k/8/6Q1/5K2/8/8/8/8 w - - 0 1", "f5f6")
+
+    expect(result.reason).to eq(:stalemate)
+    expect(result.score).to eq("1/2-1/2")
+    expect(result).to be_draw
+    expect(result).to be_stalemate
+    expect(result).not_to be_decisive
+  end
+
+  it "is a draw with insufficient material" do
+    result = play("7k/8/8/8/8/8/6r1/6K1 w - - 0 1", "g1g2")
+
+    expect(result.reason).to eq(:insufficient_material)
+    expect(result.score).to eq("1/2-1/2")
+    expect(result).to be_draw
+    expect(result).not_to be_decisive
+  end
+
+  it "calls every draw stalemate, as chess_engine does" do
+    expect(play("7k/8/8/8/8/8/6r1/6K1 w - - 0 1", "g1g2")).to be_stalemate
+    expect(described_class.new_draw(:repetition)).to be_stalemate
+  end
+
+  it "goes on while the game does" do
+    result = Silverpoint::Board.new.play_move(Silverpoint::Move.parse("e2e4"))
+
+    expect(result.reason).to be_nil
+    expect(result.score).to eq("*")
+    expect(result).not_to be_draw
+    expect(result).not_to be_decisive
+  end
+
+  describe "#final_board and #last_move" do
+    let(:fools_mate) { "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2" }
+
+    it "keep the mating position, where next_board is nil" do
+      result = play(fools_mate, "d8h4")
+
+      expect(result.next_board).to be_nil
+      expect(result.final_board.piece(Silverpoint::Position.pgn("h4")).name).to eq("queen")
+      expect(result.final_board).to be_in_check(Silverpoint::Color::White)
+      expect(result.last_move).to eq(Silverpoint::Move.parse("d8h4"))
+    end
+
+    it "keep the stalemated position" do
+      result = play("7k/8/6Q1/5K2/8/8/8/8 w - - 0 1", "f5f6")
+
+      expect(result.next_board).to be_nil
+      expect(result.final_board).to eq(board_from_fen("7k/8/5KQ1/8/8/8/8/8 b - - 1 1"))
+      expect(result.last_move).to eq(Silverpoint::Move.parse("f5f6"))
+    end
+
+    it "match next_board while the game goes on" do
+      result = Silverpoint::Board.new.play_move(Silverpoint::Move.parse("e2e4"))
+
+      expect(result.final_board).to eq(result.next_board)
+      expect(result.last_move).to eq(Silverpoint::Move.parse("e2e4"))
+    end
+
+    it "keep the board as it stood after a resignation or an illegal move" do
+      board = Silverpoint::Board.new
+
+      resigned = board.play_move(Silverpoint::Move.new_resign)
+      expect(resigned.final_board).to eq(board)
+      expect(resigned.last_move).to eq(Silverpoint::Move.new_resign)
+
+      illegal = board.play_move(Silverpoint::Move.parse("e2e5"))
+      expect(illegal.final_board).to eq(board)
+      expect(illegal.last_move).to be_nil
+    end
+
+    it "are nil for results made without a board" do
+      result = described_class.new_draw(:repetition)
+
+      expect(result.final_board).to be_nil
+      expect(result.last_move).to be_nil
+    end
+  end
+end
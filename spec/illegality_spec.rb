@@ -0,0 +1,105 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#illegality_reason" do
+  def pgn(square)
+    Silverpoint::Position.pgn(square)
+  end
+
+  def reason(fen, input)
+    m = input.is_a?(String) ? Silverpoint::Move.parse(input) : input
+    board_from_fen(fen).illegality_reason(m)
+  end
+
+  let(:start) { "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1" }
+  let(:castles) { "r3k2r/8/8/8/2b5/8/8/R3K2R w KQkq - 0 1" }
+
+  it "is nil for legal moves" do
+    expect(reason(start, "e2e4")).to be_nil
+    expect(Silverpoint::Board.new.illegality_reason(Silverpoint::Move.new_resign)).to be_nil
+  end
+
+  it "explains moving from an empty square" do
+    illegality = reason(start, "e3e4")
+
+    expect(illegality.reason).to eq(:no_piece)
+    expect(illegality.square).to eq(pgn("e3"))
+    expect(illegality.message).to eq("there's no piece on e3")
+  end
+
+  it "explains moving the other side's piece" do
+    illegality = reason(start, "e7e5")
+
+    expect(illegality.reason).to eq(:wrong_color)
+    expect(illegality.piece).to eq(Silverpoint::Board.new.piece(pgn("e7")))
+    expect(illegality.message).to eq("it's white to move, and the pawn on e7 is black")
+  end
+
+  it "explains moves a piece can't make" do
+    illegality = reason(start, "g1g3")
+
+    expect(illegality.reason).to eq(:invalid_movement)
+    expect(illegality.move).to eq(Silverpoint::Move.parse("g1g3"))
+    expect(illegality.message).to eq("a knight can't move from g1 to g3")
+  end
+
+  it "names the piece in the way" do
+    illegality = reason(start, "a1a3")
+
+    expect(illegality.reason).to eq(:blocked)
+    expect(illegality.culprit).to eq(Silverpoint::Board.new.piece(pgn("a2")))
+    expect(illegality.square).to eq(pgn("a2"))
+    expect(illegality.message).to eq("the pawn on a2 is in the way")
+
+    blocked = reason("r3k2r/8/8/8/8/8/8/RN2K2R w KQkq - 0 1", Silverpoint::Move.new_queenside_castle)
+    expect(blocked.reason).to eq(:blocked)
+    expect(blocked.square).to eq(pgn("b1"))
+  end
+
+  it "names the pinner when a pinned piece moves" do
+    illegality = reason("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1", "e2c3")
+
+    expect(illegality.reason).to eq(:king_in_check)
+    expect(illegality).to be_pinned
+    expect(illegality.culprit).to eq(board_from_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").piece(pgn("e7")))
+    expect(illegality.message).to eq("the knight on e2 is pinned to your king by the rook on e7")
+  end
+
+  it "names the checker when the king walks into check" do
+    illegality = reason("4k3/8/8/8/8/8/3r4/4K3 w - - 0 1", "e1f2")
+
+    expect(illegality.reason).to eq(:king_in_check)
+    expect(illegality).not_to be_pinned
+    expect(illegality.message).to eq("your king would be in check from the rook on d2")
+  end
+
+  it "explains castling through or out of check" do
+    [Silverpoint::Move.new_kingside_castle, "e1g1"].each do |m|
+      illegality = reason(castles, m)
+
+      expect(illegality.reason).to eq(:castling_through_check)
+      expect(illegality.square).to eq(pgn("f1"))
+      expect(illegality.message).to eq("you can't castle through f1, which the bishop on c4 attacks")
+    end
+
+    illegality = reason("4k3/4r3/8/8/8/8/8/R3K2R w KQ - 0 1", Silverpoint::Move.new_kingside_castle)
+    expect(illegality.square).to eq(pgn("e1"))
+    expect(illegality.message).to eq("you can't castle out of check from the rook on e7")
+  end
+
+  it "explains castling without the right to" do
+    illegality = reason("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1", Silverpoint::Move.new_queenside_castle)
+
+    expect(illegality.reason).to eq(:no_castling_rights)
+    expect(illegality.message).to eq("white can no longer castle queenside")
+  end
+
+  it "explains taking en passant too late" do
+    illegality = reason("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1", "e5d6")
+
+    expect(illegality.reason).to eq(:en_passant_unavailable)
+    expect(illegality.square).to eq(pgn("d5"))
+    expect(illegality.message).to eq("the pawn on d5 can't be taken en passant now")
+  end
+end
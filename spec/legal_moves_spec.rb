@@ -0,0 +1,27 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#legal_moves" do
+  def moves(*inputs)
+    inputs.map { |input| Silverpoint::Move.parse(input) }
+  end
+
+  it "includes both captures of a pawn that can take either way" do
+    board = board_from_fen("4k3/8/8/2p1p3/3P4/8/8/4K3 w - - 0 1")
+
+    expect(board.legal_moves).to include(*moves("d4c5", "d4e5"))
+  end
+
+  it "includes castling on both sides" do
+    board = board_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1")
+
+    expect(board.legal_moves).to include(Silverpoint::Move.new_kingside_castle, Silverpoint::Move.new_queenside_castle)
+  end
+
+  it "matches the moves parse_move accepts" do
+    board = board_from_fen("r3k2r/8/8/2p1p3/3P4/8/8/R3K2R w KQkq - 0 1")
+
+    expect(board.legal_moves).to all(satisfy { |m| board.parse_move(m.to_uci(board)) == m })
+  end
+end
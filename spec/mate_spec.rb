@@ -0,0 +1,45 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#find_mate" do
+  def moves(*moves)
+    moves.map { |m| Silverpoint::Move.parse(m) }
+  end
+
+  let(:back_rank) { board_from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1") }
+  let(:corner) { board_from_fen("k7/8/2K5/8/8/8/8/1R6 w - - 0 1") }
+
+  it "finds a mate in one" do
+    expect(back_rank.find_mate(1)).to eq(moves("a1a8"))
+    expect(back_rank.mating_moves(1)).to eq(moves("a1a8"))
+  end
+
+  it "finds the shortest mate when given more moves" do
+    expect(back_rank.find_mate(3)).to eq(moves("a1a8"))
+  end
+
+  it "finds a mate in two with the defence" do
+    expect(corner.find_mate(2)).to eq(moves("c6c7", "a8a7", "b1a1"))
+    expect(corner.mating_moves(2)).to eq(moves("c6c7"))
+  end
+
+  it "finds nothing without a mate in reach" do
+    expect(corner.find_mate(1)).to be_nil
+    expect(corner.mating_moves(1)).to be_empty
+
+    board = board_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")
+    expect(board.find_mate(3)).to be_nil
+    expect(board.mating_moves(3)).to be_empty
+  end
+
+  it "finds mates that take a quiet move as well as forcing ones" do
+    expect(corner.mating_moves(3)).to include(*moves("c6c7", "b1a1"))
+    expect(back_rank.mating_moves(2)).to include(*moves("a1a8"))
+  end
+
+  it "needs at least one move" do
+    expect { back_rank.find_mate(0) }.to raise_error(ArgumentError)
+    expect { back_rank.mating_moves(0) }.to raise_error(ArgumentError)
+  end
+end
@@ -0,0 +1,92 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#move_info" do
+  def pgn(square)
+    Silverpoint::Position.pgn(square)
+  end
+
+  def info(fen, m)
+    m = Silverpoint::Move.parse(m) if m.is_a?(String)
+    board_from_fen(fen).move_info(m)
+  end
+
+  let(:castles) { "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1" }
+
+  it "describes a capture" do
+    fen = "4k3/8/8/3p4/8/8/8/3QK3 w - - 0 1"
+    capture = info(fen, "d1d5")
+
+    expect(capture.piece).to eq(board_from_fen(fen).piece(pgn("d1")))
+    expect(capture.captured).to eq(board_from_fen(fen).piece(pgn("d5")))
+    expect(capture.capture_square).to eq(pgn("d5"))
+    expect(capture).to be_capture
+    expect(capture).not_to be_en_passant
+    expect(capture).not_to be_check
+    expect(capture).to be_frozen
+  end
+
+  it "finds the pawn taken en passant behind the target square" do
+    fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1"
+    en_passant = info(fen, "e5d6")
+
+    expect(en_passant).to be_en_passant
+    expect(en_passant).to be_capture
+    expect(en_passant.to).to eq(pgn("d6"))
+    expect(en_passant.capture_square).to eq(pgn("d5"))
+    expect(en_passant.captured).to eq(board_from_fen(fen).piece(pgn("d5")))
+  end
+
+  it "tells check from mate" do
+    check = info("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8")
+    expect(check).to be_check
+    expect(check).not_to be_mate
+
+    mate = info("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1a8")
+    expect(mate).to be_check
+    expect(mate).to be_mate
+  end
+
+  it "moves the rook when castling kingside" do
+    castle = info(castles, Silverpoint::Move.new_kingside_castle)
+
+    expect(castle).to be_castle
+    expect(castle).not_to be_capture
+    expect(castle.piece.name).to eq("king")
+    expect([castle.from, castle.to]).to eq([pgn("e1"), pgn("g1")])
+    expect([castle.rook_from, castle.rook_to]).to eq([pgn("h1"), pgn("f1")])
+  end
+
+  it "moves the rook when castling queenside" do
+    castle = info(castles, Silverpoint::Move.new_queenside_castle)
+
+    expect(castle).to be_castle
+    expect([castle.from, castle.to]).to eq([pgn("e1"), pgn("c1")])
+    expect([castle.rook_from, castle.rook_to]).to eq([pgn("a1"), pgn("d1")])
+  end
+
+  it "leaves castling details out of other moves" do
+    quiet = described_class.new.move_info(Silverpoint::Move.parse("e2e4"))
+
+    expect(quiet).not_to be_castle
+    expect(quiet).not_to be_capture
+    expect(quiet.rook_from).to be_nil
+    expect(quiet.captured).to be_nil
+    expect(quiet.promotion).to be_nil
+  end
+
+  it "names the piece a pawn promotes to" do
+    promotion = info("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8")
+
+    expect(promotion.promotion.name).to eq("queen")
+    expect(promotion.promotion.color).to eq(Silverpoint::Color::White)
+    expect(promotion.piece.name).to eq("pawn")
+  end
+
+  it "raises for illegal moves and resigning" do
+    expect { described_class.new.move_info(Silverpoint::Move.parse("e2e5")) }
+      .to raise_error(Silverpoint::IllegalMoveError)
+    expect { described_class.new.move_info(Silverpoint::Move.new_resign) }.to raise_error(ArgumentError)
+  end
+end
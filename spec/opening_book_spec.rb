@@ -0,0 +1,52 @@
+# frozen_string_literal: true
+
+require "silverpoint"
+
+RSpec.describe Silverpoint::Board, "#polyglot_key" do
+  def play(*moves)
+    moves.reduce(described_class.new) do |board, m|
+      board.play_move(Silverpoint::Move.parse(m)).next_board
+    end
+  end
+
+  it "matches the reference keys" do
+    expect(play.polyglot_key).to eq(0x463b96181691fc9c)
+    expect(play("e2e4").polyglot_key).to eq(0x823c9b50fd114196)
+    expect(play("e2e4", "d7d5").polyglot_key).to eq(0x0756b94461c50fb0)
+  end
+end
+
+RSpec.describe Silverpoint::OpeningBook do
+  subject(:book) { described_class.open(File.join(__dir__, "fixtures", "book.bin")) }
+
+  let(:start) { Silverpoint::Board.new }
+  let(:e4) { Silverpoint::Move.parse("e2e4") }
+
+  it "reads every entry" do
+    expect(book.size).to eq(3)
+  end
+
+  it "looks up moves with their weights in book order" do
+    expect(book.moves(start)).to eq([[e4, 10], [Silverpoint::Move.parse("d2d4"), 5]])
+
+    after_e4 = start.play_move(e4).next_board
+    expect(book.moves(after_e4)).to eq([[Silverpoint::Move.parse("e7e5"), 1]])
+  end
+
+  it "picks moves by weight" do
+    random = Object.new
+    def random.rand(total) = total - 1
+
+    expect(book.pick(start, random: random)).to eq(Silverpoint::Move.parse("d2d4"))
+  end
+
+  it "has nothing for positions out of book" do
+    board = start.play_move(Silverpoint::Move.parse("a2a3")).next_board
+    expect(book).not_to include(board)
+    expect(book.pick(board)).to be_nil
+  end
+
+  it "raises IOError for a missing file" do
+    expect { described_class.open("/nonexistent/book.bin") }.to raise_error(IOError)
+  end
+end
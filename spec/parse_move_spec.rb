@@ -0,0 +1,64 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#parse_move" do
+  def move(input)
+    Silverpoint::Move.parse(input)
+  end
+
+  let(:start) { described_class.new }
+  let(:knights) { board_from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1") }
+
+  it "reads SAN" do
+    expect(start.parse_move("e4")).to eq(move("e2e4"))
+    expect(start.parse_move("Nf3")).to eq(move("g1f3"))
+    expect(start.parse_move("1. e4")).to eq(move("e2e4"))
+  end
+
+  it "reads UCI and long algebraic notation" do
+    expect(start.parse_move("e2e4")).to eq(move("e2e4"))
+    expect(start.parse_move("e2-e4")).to eq(move("e2e4"))
+    expect(start.parse_move("Ng1-f3")).to eq(move("g1f3"))
+  end
+
+  it "reads castling and promotion" do
+    castles = board_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1")
+    expect(castles.parse_move("O-O")).to eq(Silverpoint::Move.new_kingside_castle)
+    expect(castles.parse_move("0-0-0")).to eq(Silverpoint::Move.new_queenside_castle)
+    expect(castles.parse_move("e1g1")).to eq(Silverpoint::Move.new_kingside_castle)
+
+    promotion = board_from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1")
+    expect(promotion.parse_move("e8=Q")).to eq(move("e7e8"))
+  end
+
+  it "uses the origin to tell pieces apart" do
+    expect(knights.parse_move("Nbd2")).to eq(move("b1d2"))
+    expect(knights.parse_move("Nfd2")).to eq(move("f3d2"))
+  end
+
+  it "raises AmbiguousMoveError when more than one move fits" do
+    expect { knights.parse_move("Nd2") }.to raise_error(Silverpoint::AmbiguousMoveError) { |error|
+      expect(error.input).to eq("Nd2")
+      expect(error.candidates).to contain_exactly(move("b1d2"), move("f3d2"))
+    }
+  end
+
+  it "raises IllegalMoveError with suggestions when no move fits" do
+    expect { start.parse_move("e2e5") }.to raise_error(Silverpoint::IllegalMoveError) { |error|
+      expect(error.move).to eq(move("e2e5"))
+      expect(error.board).to eq(start)
+      expect(error.suggestions).to include(move("e2e4"))
+    }
+    expect { start.parse_move("Nf6") }.to raise_error(Silverpoint::IllegalMoveError) { |error|
+      expect(error.move).to be_nil
+    }
+  end
+
+  it "raises ParseError for input that isn't a move" do
+    expect { start.parse_move("z9") }.to raise_error(Silverpoint::ParseError) { |error|
+      expect(error.column).to eq(0)
+      expect(error.expected).to eq("file a-h")
+    }
+  end
+end
@@ -0,0 +1,54 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Move, ".parse" do
+  let(:e4) { described_class.new_piece(Silverpoint::Position.pgn("e2"), Silverpoint::Position.pgn("e4")) }
+
+  it "reads squares with or without a separator" do
+    ["e2e4", "e2 e4", "e2 to e4", "  e2e4  ", "E2E4"].each do |input|
+      expect(described_class.parse(input)).to eq(e4)
+    end
+  end
+
+  it "reads castling and resigning" do
+    ["O-O", "0-0", "o-o", "castle kingside", "kingside castle"].each do |input|
+      expect(described_class.parse(input)).to eq(described_class.new_kingside_castle)
+    end
+    ["O-O-O", "0-0-0", "o-o-o", "castle queenside", "queenside castle"].each do |input|
+      expect(described_class.parse(input)).to eq(described_class.new_queenside_castle)
+    end
+    %w[resign resigns].each do |input|
+      expect(described_class.parse(input)).to eq(described_class.new_resign)
+    end
+  end
+
+  it "reports the column parsing failed at" do
+    expect { described_class.parse("e2 x4") }.to raise_error(Silverpoint::ParseError) { |error|
+      expect(error.column).to eq(3)
+      expect(error.expected).to eq("file a-h")
+    }
+    expect { described_class.parse("e2 by e4") }.to raise_error(Silverpoint::ParseError) { |error|
+      expect(error.column).to eq(3)
+      expect(error.expected).to eq("\"to\"")
+    }
+    expect { described_class.parse("") }.to raise_error(Silverpoint::ParseError)
+  end
+end
+
+RSpec.describe Silverpoint::Position, ".pgn" do
+  it "reads a square" do
+    expect(described_class.pgn("e4")).to eq(described_class.new(3, 4))
+    expect(described_class.pgn(" E4 ")).to eq(described_class.new(3, 4))
+  end
+
+  it "reports the column parsing failed at" do
+    expect { described_class.pgn("z4") }.to raise_error(Silverpoint::ParseError) { |error|
+      expect(error.input).to eq("z4")
+      expect(error.column).to eq(0)
+    }
+    expect { described_class.pgn("e") }.to raise_error(Silverpoint::ParseError) { |error|
+      expect(error.column).to eq(1)
+    }
+  end
+end
@@ -0,0 +1,60 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#pawn_structure" do
+  def pawns(board, *squares)
+    squares.map { |square| board.piece(Silverpoint::Position.pgn(square)) }
+  end
+
+  let(:white) { Silverpoint::Color::White }
+  let(:black) { Silverpoint::Color::Black }
+  # White has doubled, isolated and passed pawns on d4 and d5, and a pair on a2 and b3.
+  let(:scattered) { board_from_fen("4k3/1p4p1/8/3P4/3P4/1P6/P5P1/4K3 w - - 0 1") }
+  # The pawn on d3 can't step up while c5 covers d4.
+  let(:backward) { board_from_fen("4k3/8/8/2p5/2P1P3/3P4/8/4K3 w - - 0 1") }
+
+  it "finds passed pawns" do
+    expect(scattered.pawn_structure(white).passed).to contain_exactly(*pawns(scattered, "d4", "d5"))
+    expect(scattered.pawn_structure(black).passed).to be_empty
+    expect(backward.pawn_structure(white).passed).to eq(pawns(backward, "e4"))
+  end
+
+  it "finds isolated pawns" do
+    expect(scattered.pawn_structure(white).isolated).to contain_exactly(*pawns(scattered, "d4", "d5", "g2"))
+    expect(scattered.pawn_structure(black).isolated).to contain_exactly(*pawns(scattered, "b7", "g7"))
+    expect(backward.pawn_structure(white).isolated).to be_empty
+  end
+
+  it "finds doubled pawns" do
+    expect(scattered.pawn_structure(white).doubled).to contain_exactly(*pawns(scattered, "d4", "d5"))
+    expect(backward.pawn_structure(white).doubled).to be_empty
+  end
+
+  it "finds backward pawns" do
+    expect(backward.pawn_structure(white).backward).to eq(pawns(backward, "d3"))
+    expect(scattered.pawn_structure(white).backward).to be_empty
+  end
+
+  it "counts pawn islands" do
+    expect(scattered.pawn_structure(white).islands).to eq(3)
+    expect(scattered.pawn_structure(black).islands).to eq(2)
+    expect(backward.pawn_structure(white).islands).to eq(1)
+  end
+
+  it "finds open and half-open files" do
+    expect(scattered.pawn_structure(white).open_files).to eq(%w[c e f h])
+    expect(scattered.pawn_structure(white).half_open_files).to be_empty
+    expect(scattered.pawn_structure(black).half_open_files).to eq(%w[a d])
+    expect(backward.pawn_structure(black).half_open_files).to eq(%w[d e])
+  end
+end
+
+RSpec.describe Silverpoint::Board, "#best_next_move" do
+  it "weighs pawn structure when choosing between equal captures" do
+    # Both captures win a pawn, and chess_engine's material and square tables alone pick dxc6.
+    board = board_from_fen("8/1k6/2p1p3/3P3p/6p1/7P/6K1/8 w - - 0 1")
+
+    expect(board.best_next_move(1).first).to eq(Silverpoint::Move.parse("d5e6"))
+  end
+end
@@ -0,0 +1,106 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Position do
+  def squares(*names)
+    names.map { |name| described_class.pgn(name) }
+  end
+
+  let(:a1) { described_class.pgn("a1") }
+  let(:h1) { described_class.pgn("h1") }
+  let(:a8) { described_class.pgn("a8") }
+  let(:h8) { described_class.pgn("h8") }
+  let(:e4) { described_class.pgn("e4") }
+
+  describe "indices" do
+    it "count from a1 to h8 rank by rank" do
+      expect([a1, h1, a8, h8].map(&:index)).to eq([0, 7, 56, 63])
+      expect(described_class.from_index(28)).to eq(e4)
+    end
+
+    it "name files with letters and ranks with numbers" do
+      expect([a1, h8, e4].map { |square| [square.file, square.rank] }).to eq([["a", 1], ["h", 8], ["e", 4]])
+    end
+
+    it "reject squares off the board" do
+      expect { described_class.from_index(64) }.to raise_error(ArgumentError)
+      expect { described_class.from_index(-1) }.to raise_error(ArgumentError)
+      expect { described_class.new(8, 0).index }.to raise_error(Silverpoint::InvalidPositionError)
+    end
+  end
+
+  it "colors a1 and h8 dark and h1 and a8 light" do
+    white = Silverpoint::Color::White
+    black = Silverpoint::Color::Black
+
+    expect([a1, h1, a8, h8].map(&:square_color)).to eq([black, white, white, black])
+  end
+
+  it "measures king and rook distances" do
+    expect(a1.distance(h8)).to eq(7)
+    expect(a1.manhattan_distance(h8)).to eq(14)
+    expect(e4.distance(squares("f6").first)).to eq(2)
+    expect(e4.manhattan_distance(squares("f6").first)).to eq(3)
+    expect(e4.distance(e4)).to eq(0)
+  end
+
+  describe "#between" do
+    it "lists the squares strictly between, going from the receiver" do
+      expect(a1.between(h8)).to eq(squares("b2", "c3", "d4", "e5", "f6", "g7"))
+      expect(h8.between(a1)).to eq(squares("g7", "f6", "e5", "d4", "c3", "b2"))
+      expect(a1.between(a8)).to eq(squares("a2", "a3", "a4", "a5", "a6", "a7"))
+    end
+
+    it "is empty for neighbours and squares off a line" do
+      expect(a1.between(squares("b2").first)).to be_empty
+      expect(a1.between(squares("b3").first)).to be_empty
+    end
+  end
+
+  describe "#ray" do
+    it "runs to the edge of the board, nearest first" do
+      expect(a1.ray(:north_east)).to eq(squares("b2", "c3", "d4", "e5", "f6", "g7", "h8"))
+      expect(h8.ray(:west)).to eq(squares("g8", "f8", "e8", "d8", "c8", "b8", "a8"))
+      expect(e4.ray(:south)).to eq(squares("e3", "e2", "e1"))
+    end
+
+    it "is empty off the edge of the board" do
+      expect(a1.ray(:south)).to be_empty
+      expect(h8.ray(:north_east)).to be_empty
+    end
+
+    it "rejects unknown directions" do
+      expect { a1.ray(:up) }.to raise_error(ArgumentError)
+    end
+  end
+
+  it "mirrors files and flips ranks" do
+    expect(a1.mirror).to eq(h1)
+    expect(a1.flip).to eq(a8)
+    expect(e4.mirror.mirror).to eq(e4)
+  end
+
+  it "steps to the next square with succ" do
+    expect(a1.succ).to eq(squares("b1").first)
+    expect(h1.succ).to eq(squares("a2").first)
+    expect(h8.succ).to be_nil
+  end
+
+  it "orders squares from a1 to h8" do
+    expect(a1 <=> h1).to eq(-1)
+    expect(h1 <=> squares("a2").first).to eq(-1)
+    expect(a1 <=> described_class.new(0, 0)).to eq(0)
+    expect(h8 <=> a1).to eq(1)
+    expect(a1 <=> "a1").to be_nil
+    expect((a1..squares("c1").first).to_a).to eq(squares("a1", "b1", "c1"))
+  end
+
+  it "enumerates every square" do
+    expect(described_class.count).to eq(64)
+    expect(described_class.first).to eq(a1)
+    expect(described_class.to_a.last).to eq(h8)
+    expect(described_class.each).to be_a(Enumerator)
+    expect(described_class.to_a).to eq(described_class.all)
+  end
+end
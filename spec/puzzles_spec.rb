@@ -0,0 +1,36 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Puzzles, ".find" do
+  let(:fork) { board_from_fen("r3k3/8/8/3N4/8/8/8/4K3 w - - 0 1") }
+  # Rb1 wins the pawn, a little over one pawn better than anything else.
+  let(:pawn_up) { board_from_fen("4k3/8/8/1p6/8/8/8/R3K3 w - - 0 1") }
+  # Kxf2 is far better than the rest, but only gets back to level material.
+  let(:recapture) { board_from_fen("4k3/8/8/8/8/8/5n2/4K3 w - - 0 1") }
+
+  it "finds a single winning move and plays out the line" do
+    puzzles = described_class.find([Silverpoint::Board.new, fork])
+
+    expect(puzzles.map(&:index)).to eq([1])
+    expect(puzzles.first.board).to eq(fork)
+    expect(puzzles.first.solution.first).to eq(Silverpoint::Move.parse("d5c7"))
+    expect(puzzles.first.solution.length).to eq(3)
+    expect(puzzles.first.margin).to be >= 30
+  end
+
+  it "needs the best move to beat the others by three pawns by default" do
+    expect(described_class.find([pawn_up])).to be_empty
+    expect(described_class.find([pawn_up], margin: 3).map(&:index)).to eq([0])
+    expect(described_class.find([fork], margin: 100)).to be_empty
+  end
+
+  it "needs the best move to leave the side to move winning" do
+    expect(described_class.find([recapture])).to be_empty
+    expect(described_class.find([recapture], winning: 0).map(&:index)).to eq([0])
+  end
+
+  it "stops the solution at the given length" do
+    expect(described_class.find([fork], length: 1).first.solution).to eq([Silverpoint::Move.parse("d5c7")])
+  end
+end
@@ -0,0 +1,49 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe "Silverpoint values" do
+  let(:values) do
+    board = Silverpoint::Board.new
+    [
+      board,
+      Silverpoint::Move.parse("e2e4"),
+      Silverpoint::Position.pgn("e4"),
+      board.piece(Silverpoint::Position.pgn("e2")),
+      Silverpoint::Color::White,
+      Silverpoint::GameResult.new_draw(:stalemate)
+    ]
+  end
+
+  let(:motif) do
+    board = board_from_fen("r3k3/8/8/3N4/8/8/8/4K3 w - - 0 1")
+    Silverpoint::Tactics.detect(board, Silverpoint::Move.parse("d5c7")).first
+  end
+
+  it "are frozen and shareable between Ractors" do
+    [*values, motif].each do |value|
+      expect(value).to be_frozen
+      expect(Ractor.shareable?(value)).to be(true), "#{value.class} isn't shareable"
+    end
+  end
+
+  it "stay frozen through dup and Marshal" do
+    values.each do |value|
+      expect(value.dup).to be_frozen
+      expect(Marshal.load(Marshal.dump(value))).to be_frozen
+    end
+  end
+end
+
+RSpec.describe Silverpoint::Board, "#best_next_move" do
+  it "searches in several Ractors at once" do
+    board = described_class.new
+    boards = [board, board.apply_eval_move(Silverpoint::Move.parse("e2e4"))] * 2
+
+    ractors = boards.map { |start| Ractor.new(start) { |b| b.best_next_move(2).first } }
+    moves = ractors.map(&:take)
+
+    expect(moves).to all(be_a(Silverpoint::Move))
+    expect(moves).to eq(boards.map { |b| b.best_next_move(2).first })
+  end
+end
@@ -0,0 +1,63 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Review, ".annotate" do
+  def moves(*inputs)
+    inputs.map { |input| Silverpoint::Move.parse(input) }
+  end
+
+  # 3...Nf6?? lets White mate with 4. Qxf7#.
+  let(:scholars_mate) { moves("e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7") }
+  let(:pawn_up) { board_from_fen("4k3/8/8/1p6/8/8/8/R3K3 w - - 0 1") }
+
+  it "classifies each move of a game by how much it loses" do
+    annotations = described_class.annotate(Silverpoint::Board.new, scholars_mate)
+
+    expect(annotations.map(&:ply)).to eq((0..6).to_a)
+    expect(annotations.map(&:move)).to eq(scholars_mate)
+    expect(annotations.map(&:classification))
+      .to eq(%i[good inaccuracy inaccuracy good good blunder best])
+  end
+
+  it "names the better move for a blunder" do
+    blunder = described_class.annotate(Silverpoint::Board.new, scholars_mate)[5]
+
+    expect(blunder.best_move).not_to eq(Silverpoint::Move.parse("g8f6"))
+    expect(blunder.loss).to be > 30
+    expect(blunder.nag).to eq(4)
+  end
+
+  it "calls a loss under half a pawn good and from half a pawn an inaccuracy" do
+    opening = described_class.annotate(Silverpoint::Board.new, moves("e2e4", "e7e5", "d1h5"))
+
+    expect(opening.map(&:loss)).to eq([1.5, 6.0, 5.0])
+    expect(opening.map(&:nag)).to eq([nil, 6, 6])
+  end
+
+  it "calls a loss from one pawn a mistake and from three pawns a blunder" do
+    mistake, = described_class.annotate(pawn_up, moves("a1a8"))
+    blunder, = described_class.annotate(pawn_up, moves("a1a4"))
+
+    expect(mistake.loss).to eq(11.5)
+    expect(mistake.classification).to eq(:mistake)
+    expect(mistake.nag).to eq(2)
+    expect(mistake.best_move).to eq(Silverpoint::Move.parse("a1b1"))
+    expect(blunder.loss).to eq(65.0)
+    expect(blunder.classification).to eq(:blunder)
+    expect(blunder.nag).to eq(4)
+  end
+
+  it "leaves best moves without a NAG" do
+    best, = described_class.annotate(pawn_up, moves("a1b1"))
+
+    expect(best.classification).to eq(:best)
+    expect(best.loss).to eq(0)
+    expect(best.nag).to be_nil
+  end
+
+  it "raises IllegalMoveError naming the illegal move" do
+    expect { described_class.annotate(Silverpoint::Board.new, moves("e2e4", "e2e4")) }
+      .to raise_error(Silverpoint::IllegalMoveError, /move 2/)
+  end
+end
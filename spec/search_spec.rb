@@ -0,0 +1,24 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#best_next_move" do
+  it "considers every pawn capture" do
+    %w[4k3/8/8/2p1q3/3P4/8/8/4K3 4k3/8/8/2q1p3/3P4/8/8/4K3].zip(%w[d4e5 d4c5]).each do |placement, best|
+      board = board_from_fen("#{placement} w - - 0 1")
+
+      expect(board.best_next_move(0).first).to eq(Silverpoint::Move.parse(best))
+    end
+  end
+
+  it "rejects a negative depth" do
+    expect { described_class.new.best_next_move(-1) }.to raise_error(ArgumentError)
+    expect { described_class.new.best_next_move(-1, wtime: 1000, btime: 1000) }.to raise_error(ArgumentError)
+  end
+end
+
+RSpec.describe Silverpoint::Board, "#worst_next_move" do
+  it "rejects a negative depth" do
+    expect { described_class.new.worst_next_move(-1) }.to raise_error(ArgumentError)
+  end
+end
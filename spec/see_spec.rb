@@ -0,0 +1,52 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board, "#see" do
+  def see(fen, input)
+    board_from_fen(fen).see(Silverpoint::Move.parse(input))
+  end
+
+  it "scores a capture of an undefended piece" do
+    expect(see("4k3/8/8/3p4/8/8/8/3QK3 w - - 0 1", "d1d5")).to eq(1)
+  end
+
+  it "counts a slider x-raying through the piece in front" do
+    expect(see("3r2k1/8/8/3p4/8/8/3R4/3R2K1 w - - 0 1", "d2d5")).to eq(1)
+    expect(see("3r2k1/8/8/3p4/8/8/3R4/6K1 w - - 0 1", "d2d5")).to eq(-4)
+  end
+
+  it "scores a capture that loses material" do
+    expect(see("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5")).to eq(-8)
+  end
+
+  it "scores quiet moves that hang nothing as zero" do
+    expect(described_class.new.see(Silverpoint::Move.parse("e2e4"))).to eq(0)
+  end
+
+  it "scores en passant as taking the pawn that moved past" do
+    expect(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6")).to eq(1)
+    expect(see("4k3/2p5/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6")).to eq(0)
+  end
+
+  it "scores moves that are only illegal because of a pin" do
+    expect(see("4k3/4r3/8/8/3p4/8/4N3/4K3 w - - 0 1", "e2d4")).to eq(1)
+  end
+
+  it "raises ArgumentError for moves the piece can't make" do
+    expect { see("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", "e2d1") }.to raise_error(ArgumentError)
+    expect { see("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", "e2d3") }.to raise_error(ArgumentError)
+    expect { see("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1", "e5d6") }.to raise_error(ArgumentError)
+  end
+end
+
+RSpec.describe Silverpoint::Board, "#see_ge" do
+  it "compares the exchange with a threshold" do
+    board = board_from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1")
+    m = Silverpoint::Move.parse("d1d5")
+
+    expect(board.see_ge(m, 0)).to be(false)
+    expect(board.see_ge(m, -8)).to be(true)
+    expect(board.see_ge(m, -9)).to be(true)
+  end
+end
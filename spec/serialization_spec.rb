@@ -0,0 +1,78 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe "Silverpoint serialization" do
+  def pgn(square)
+    Silverpoint::Position.pgn(square)
+  end
+
+  let(:boards) do
+    [
+      Silverpoint::Board.new,
+      # Black to move with en passant on e3.
+      Silverpoint::Board.new.play_move(Silverpoint::Move.parse("e2e4")).next_board,
+      board_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1"),
+      board_from_fen("4k3/4P3/8/8/8/8/8/4K3 w - - 0 1")
+    ]
+  end
+
+  let(:moves) do
+    [
+      Silverpoint::Move.parse("e2e4"),
+      Silverpoint::Move.new_kingside_castle,
+      Silverpoint::Move.new_queenside_castle,
+      # A promotion, which chess_engine writes as a plain move to the last rank.
+      Silverpoint::Move.parse("e7e8"),
+      Silverpoint::Move.new_resign
+    ]
+  end
+
+  let(:positions) { [pgn("a1"), pgn("e4"), pgn("h8")] }
+  let(:pieces) { [pgn("e1"), pgn("d8"), pgn("b2")].map { |square| Silverpoint::Board.new.piece(square) } }
+  let(:colors) { [Silverpoint::Color::White, Silverpoint::Color::Black] }
+
+  it "keeps castling rights and en passant through the packed codec" do
+    boards.each do |board|
+      expect(Marshal.load(Marshal.dump(board))).to eq(board)
+    end
+    expect(boards[1].to_h[:en_passant]).to eq("e3")
+    expect(boards[2].to_h[:castling]).to eq("Kq")
+  end
+
+  it "round-trips values through Marshal" do
+    (moves + positions + pieces + colors).each do |value|
+      expect(Marshal.load(Marshal.dump(value))).to eq(value)
+    end
+  end
+
+  it "round-trips values through to_h and from_h" do
+    { Silverpoint::Board => boards, Silverpoint::Move => moves,
+      Silverpoint::Position => positions, Silverpoint::Piece => pieces }.each do |klass, values|
+      values.each { |value| expect(klass.from_h(value.to_h)).to eq(value) }
+    end
+  end
+
+  it "round-trips values through JSON" do
+    { Silverpoint::Board => boards, Silverpoint::Move => moves,
+      Silverpoint::Position => positions, Silverpoint::Piece => pieces,
+      Silverpoint::Color => colors }.each do |klass, values|
+      values.each { |value| expect(klass.from_json(value.to_json)).to eq(value) }
+    end
+  end
+
+  it "raises on corrupt dumps rather than crashing" do
+    {
+      Silverpoint::Board => ["", "\xFF".b * 34, "\x00".b * 35],
+      Silverpoint::Move => ["", "\xF0\x00".b],
+      Silverpoint::Position => ["", "\x40".b],
+      Silverpoint::Piece => ["", "\x07\x00".b, "\x01\x40".b],
+      Silverpoint::Color => ["", "\x02".b],
+      Silverpoint::GameResult => ["", "\x09\x00\x00".b]
+    }.each do |klass, dumps|
+      dumps.each do |dump|
+        expect { klass._load(dump) }.to raise_error(ArgumentError)
+      end
+    end
+  end
+end
@@ -0,0 +1,43 @@
+# frozen_string_literal: true
+
+require "silverpoint"
+
+module FenHelper
+  PIECE_NAMES = {
+    "p" => "pawn",
+    "n" => "knight",
+    "b" => "bishop",
+    "r" => "rook",
+    "q" => "queen",
+    "k" => "king"
+  }.freeze
+
+  # The gem only writes FEN, so specs build their boards through `Board.from_h`.
+  def board_from_fen(fen)
+    placement, turn, castling, en_passant = fen.split
+    pieces = placement.split("/").each_with_index.flat_map do |rank, i|
+      file = 0
+      rank.chars.filter_map do |c|
+        if c.match?(/\d/)
+          file += c.to_i
+          next
+        end
+
+        square = "#{"abcdefgh"[file]}#{8 - i}"
+        file += 1
+        { name: PIECE_NAMES.fetch(c.downcase), color: c == c.upcase ? "white" : "black", position: square }
+      end
+    end
+
+    Silverpoint::Board.from_h(
+      pieces: pieces,
+      turn: turn == "b" ? "black" : "white",
+      castling: castling || "-",
+      en_passant: en_passant == "-" ? nil : en_passant
+    )
+  end
+end
+
+RSpec.configure do |config|
+  config.include FenHelper
+end
@@ -0,0 +1,27 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Board do
+  describe "#to_svg" do
+    let(:svg) { described_class.new.to_svg(coordinates: false) }
+
+    it "defines one outline per piece type" do
+      names = svg.scan(/<path id="piece-(\w+)"/).flatten
+      expect(names).to contain_exactly("king", "queen", "rook", "bishop", "knight", "pawn")
+    end
+
+    it "places every piece with a reference to its outline" do
+      expect(svg.scan("<use ").size).to eq(32)
+      expect(svg.scan('xlink:href="#piece-pawn"').size).to eq(16)
+    end
+
+    it "does not depend on fonts for the pieces" do
+      expect(svg).not_to include("<text")
+    end
+
+    it "labels the board edges with coordinates by default" do
+      expect(described_class.new.to_svg).to include("<text")
+    end
+  end
+end
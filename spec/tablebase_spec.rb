@@ -0,0 +1,114 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+require "tmpdir"
+
+RSpec.describe Silverpoint::Tablebase do
+  subject(:tablebase) { described_class.new(File.join(__dir__, "fixtures", "syzygy")) }
+
+  let(:move) { Silverpoint::Move }
+
+  it "covers the pieces of its largest table" do
+    expect(tablebase.max_pieces).to eq(3)
+  end
+
+  context "with a queen mating in one" do
+    let(:board) { board_from_fen("7k/8/5K2/8/8/8/8/6Q1 w - - 0 1") }
+
+    it "probes a win" do
+      expect(tablebase.probe_wdl(board)).to eq(described_class::WIN)
+      expect(tablebase.probe_dtz(board)).to eq(1)
+    end
+
+    it "finds the mate" do
+      expect(tablebase.best_move(board)).to eq(move.parse("g1g7"))
+    end
+  end
+
+  it "probes the loss for the side being mated" do
+    board = board_from_fen("7k/8/5K2/8/8/8/8/6Q1 b - - 0 1")
+
+    expect(tablebase.probe_wdl(board)).to eq(described_class::LOSS)
+    expect(tablebase.probe_dtz(board)).to eq(-2)
+  end
+
+  it "probes a draw when the queen can be taken" do
+    board = board_from_fen("7k/6Q1/8/8/8/8/8/K7 b - - 0 1")
+
+    expect(tablebase.probe_wdl(board)).to eq(described_class::DRAW)
+    expect(tablebase.probe_dtz(board)).to eq(0)
+    expect(tablebase.best_move(board)).to eq(move.parse("h8g7"))
+  end
+
+  it "probes the rook table" do
+    board = board_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")
+
+    expect(tablebase.probe_wdl(board)).to eq(described_class::WIN)
+    expect(tablebase.probe_dtz(board)).to eq(23)
+    expect(tablebase.best_move(board_from_fen("4k3/8/4K3/8/8/8/8/R7 w - - 0 1"))).to eq(move.parse("a1a8"))
+  end
+
+  it "probes with the colors swapped" do
+    board = board_from_fen("7K/8/5k2/8/8/8/8/6q1 b - - 0 1")
+
+    expect(tablebase.probe_wdl(board)).to eq(described_class::WIN)
+    expect(tablebase.best_move(board)).to eq(move.parse("g1g7"))
+  end
+
+  it "returns nil for positions without a table" do
+    [board_from_fen("4k3/8/8/8/8/8/8/1B2K3 w - - 0 1"), Silverpoint::Board.new].each do |board|
+      expect(tablebase.probe_wdl(board)).to be_nil
+      expect(tablebase.probe_dtz(board)).to be_nil
+      expect(tablebase.best_move(board)).to be_nil
+    end
+  end
+
+  it "raises IOError for a directory that can't be read" do
+    expect { described_class.new(File.join(__dir__, "fixtures", "missing")) }.to raise_error(IOError)
+  end
+
+  context "with a damaged table" do
+    let(:board) { board_from_fen("7k/8/5K2/8/8/8/8/6Q1 w - - 0 1") }
+
+    def damaged
+      Dir.mktmpdir do |dir|
+        table = File.binread(File.join(__dir__, "fixtures", "syzygy", "KQvK.rtbw"))
+        yield table
+        File.binwrite(File.join(dir, "KQvK.rtbw"), table)
+        described_class.new(dir).probe_wdl(board)
+      end
+    end
+
+    it "raises IOError when the table is cut short" do
+      expect { damaged { |table| table.slice!(12..) } }.to raise_error(IOError, /truncated/)
+    end
+
+    it "raises IOError for code lengths that don't fit together" do
+      # The shortest code length, one more than the longest.
+      expect { damaged { |table| table.setbyte(19, table.getbyte(18) + 1) } }.to raise_error(IOError, /bits/)
+    end
+  end
+
+  # The fixtures come from spec/fixtures/syzygy/generator, so they can only show that reading
+  # agrees with writing. Point SYZYGY_PATH at real tables to check the reader against those.
+  context "with real tables", if: ENV.key?("SYZYGY_PATH") do
+    subject(:tablebase) { described_class.new(ENV.fetch("SYZYGY_PATH")) }
+
+    it "probes the queen and rook tables" do
+      mate_in_one = board_from_fen("7k/8/5K2/8/8/8/8/6Q1 w - - 0 1")
+      rook = board_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")
+
+      expect(tablebase.probe_wdl(mate_in_one)).to eq(described_class::WIN)
+      expect(tablebase.probe_dtz(mate_in_one)).to eq(1)
+      expect(tablebase.best_move(mate_in_one)).to eq(move.parse("g1g7"))
+      expect(tablebase.probe_wdl(rook)).to eq(described_class::WIN)
+      # Real tables may round a DTZ by one ply.
+      expect(tablebase.probe_dtz(rook)).to be_within(1).of(23)
+    end
+
+    it "probes the losing side and draws" do
+      expect(tablebase.probe_wdl(board_from_fen("7k/8/5K2/8/8/8/8/6Q1 b - - 0 1"))).to eq(described_class::LOSS)
+      expect(tablebase.probe_wdl(board_from_fen("7k/6Q1/8/8/8/8/8/K7 b - - 0 1"))).to eq(described_class::DRAW)
+    end
+  end
+end
@@ -0,0 +1,79 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Tactics, ".detect" do
+  def motifs(fen, input)
+    board = board_from_fen(fen)
+    described_class.detect(board, Silverpoint::Move.parse(input))
+  end
+
+  def motif(fen, input, kind)
+    motifs(fen, input).find { |motif| motif.kind == kind }
+  end
+
+  def pieces(fen, *squares)
+    board = board_from_fen(fen)
+    squares.map { |square| board.piece(Silverpoint::Position.pgn(square)) }
+  end
+
+  it "finds a fork" do
+    fen = "r3k3/8/8/3N4/8/8/8/4K3 w - - 0 1"
+
+    expect(motif(fen, "d5c7", :fork).targets).to contain_exactly(*pieces(fen, "a8", "e8"))
+  end
+
+  it "finds a pin" do
+    fen = "4k3/8/2n5/8/8/8/8/4KB2 w - - 0 1"
+
+    expect(motif(fen, "f1b5", :pin).targets).to eq(pieces(fen, "c6", "e8"))
+  end
+
+  it "finds a skewer" do
+    fen = "3q4/8/8/3k4/8/8/8/R5K1 w - - 0 1"
+
+    expect(motif(fen, "a1d1", :skewer).targets).to eq(pieces(fen, "d5", "d8"))
+  end
+
+  it "finds a discovered attack" do
+    fen = "4k3/8/8/8/8/8/4N3/4R1K1 w - - 0 1"
+    discovered = motif(fen, "e2c3", :discovered_attack)
+
+    expect(discovered.piece).to eq(pieces(fen, "e1").first)
+    expect(discovered.targets).to eq(pieces(fen, "e8"))
+  end
+
+  it "finds a double check" do
+    fen = "4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1"
+
+    expect(motifs(fen, "e4f6").map(&:kind)).to contain_exactly(:discovered_attack, :double_check)
+    expect(motif(fen, "e4f6", :double_check).targets).to eq(pieces(fen, "e8"))
+  end
+
+  it "finds a hanging piece" do
+    fen = "4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1"
+
+    expect(motif(fen, "d1d5", :hanging_piece).targets).to eq(pieces(fen, "d5"))
+  end
+
+  it "finds a back-rank check" do
+    fen = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"
+
+    expect(motif(fen, "a1a8", :back_rank).targets).to eq(pieces(fen, "g8"))
+  end
+
+  it "finds a defender being taken" do
+    fen = "4k3/8/3n4/1b6/8/8/8/1R1RK3 w - - 0 1"
+
+    expect(motif(fen, "d1d6", :removal_of_defender).targets).to eq(pieces(fen, "b5"))
+  end
+
+  it "finds nothing in a quiet move" do
+    expect(described_class.detect(Silverpoint::Board.new, Silverpoint::Move.parse("e2e4"))).to be_empty
+  end
+
+  it "raises IllegalMoveError for illegal moves" do
+    expect { described_class.detect(Silverpoint::Board.new, Silverpoint::Move.parse("e2e5")) }
+      .to raise_error(Silverpoint::IllegalMoveError)
+  end
+end
@@ -0,0 +1,62 @@
+# frozen_string_literal: true
+
+require "spec_helper"
+
+RSpec.describe Silverpoint::Move, "UCI notation" do
+  let(:start) { Silverpoint::Board.new }
+  let(:castles) { board_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1") }
+  let(:promotion) { board_from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1") }
+
+  it "round-trips ordinary moves" do
+    %w[e2e4 g1f3 b1c3 d2d4].each do |uci|
+      m = described_class.from_uci(uci, start)
+      expect(m).to eq(described_class.parse(uci))
+      expect(m.to_uci(start)).to eq(uci)
+    end
+  end
+
+  it "writes castling as the king moving two squares" do
+    expect(described_class.from_uci("e1g1", castles)).to eq(described_class.new_kingside_castle)
+    expect(described_class.from_uci("e1c1", castles)).to eq(described_class.new_queenside_castle)
+    expect(described_class.new_kingside_castle.to_uci(castles)).to eq("e1g1")
+    expect(described_class.new_queenside_castle.to_uci(castles)).to eq("e1c1")
+    expect(described_class.new_kingside_castle.to_uci(castles.change_turn)).to eq("e8g8")
+  end
+
+  it "writes Chess960 castling as the king taking its rook" do
+    expect(described_class.from_uci("e1h1", castles)).to eq(described_class.new_kingside_castle)
+    expect(described_class.new_kingside_castle.to_uci(castles, chess960: true)).to eq("e1h1")
+    expect(described_class.new_queenside_castle.to_uci(castles, chess960: true)).to eq("e1a1")
+  end
+
+  it "writes promotions with the queen" do
+    m = described_class.from_uci("e7e8q", promotion)
+
+    expect(m).to eq(described_class.parse("e7e8"))
+    expect(m.to_uci(promotion)).to eq("e7e8q")
+    expect(described_class.from_uci("e7e8", promotion)).to eq(m)
+  end
+
+  it "rejects malformed input" do
+    {
+      "e7e8n" => [4, "promotion to a queen"],
+      "e2e4q" => [4, "end of move"],
+      "e2e4e5" => [4, "end of move"],
+      "e2e" => [3, "rank 1-8"],
+      "z2e4" => [0, "file a-h"],
+      "" => [0, "file a-h"]
+    }.each do |uci, (column, expected)|
+      board = uci.start_with?("e7") ? promotion : start
+      expect { described_class.from_uci(uci, board) }.to raise_error(Silverpoint::ParseError) { |error|
+        expect(error.column).to eq(column)
+        expect(error.expected).to eq(expected)
+      }
+    end
+    expect { described_class.from_uci("e2e9", start) }.to raise_error(Silverpoint::InvalidPositionError)
+  end
+
+  it "can't write every move without a board" do
+    expect { described_class.new_kingside_castle.to_uci }.to raise_error(ArgumentError)
+    expect { described_class.new_resign.to_uci(start) }.to raise_error(ArgumentError)
+  end
+end